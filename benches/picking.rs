@@ -0,0 +1,109 @@
+//! `cargo bench`: ray tests against spheres of increasing triangle count
+//! `triangles` is the narrow phase alone (every triangle of one mesh), `cast_ray` the whole pick
+//! over a scene, for comparing broad phase changes against
+
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use bevy_hover::{
+    cast_ray, mesh_triangle_iter, moller_trumbore, Hoverable, MouseRayPlugin, DEFAULT_EPSILON,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// (sectors, stacks) of the benchmarked spheres, 2 triangles per sector and stack
+const SIZES: [(usize, usize); 3] = [(8, 4), (32, 16), (128, 64)];
+
+fn sphere(sectors: usize, stacks: usize) -> Mesh {
+    Mesh::from(shape::UVSphere {
+        radius: 1.0,
+        sectors,
+        stacks,
+    })
+}
+
+/// Rays from in front of a unit sphere at the origin: through its center, grazing its edge, and
+/// clear of it
+fn rays() -> [(&'static str, Ray); 3] {
+    let ray = |origin: Vec3| Ray {
+        origin,
+        direction: Vec3::NEG_Z,
+    };
+    [
+        ("hit center", ray(Vec3::new(0.0, 0.0, 5.0))),
+        ("hit edge", ray(Vec3::new(0.95, 0.0, 5.0))),
+        ("miss", ray(Vec3::new(2.0, 0.0, 5.0))),
+    ]
+}
+
+/// Nearest hit with every triangle of `mesh`, the same search `check_intersect` does
+fn nearest_triangle(mesh: &Mesh, ray: Ray) -> Option<f32> {
+    mesh_triangle_iter(mesh)?
+        .filter_map(|[v0, v1, v2]| {
+            moller_trumbore(ray.origin, ray.direction, v0, v1, v2, DEFAULT_EPSILON, 0.0)
+        })
+        .min_by(f32::total_cmp)
+}
+
+fn triangles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("triangles");
+    for (sectors, stacks) in SIZES {
+        let mesh = sphere(sectors, stacks);
+        for (name, ray) in rays() {
+            group.bench_with_input(
+                BenchmarkId::new(name, sectors * stacks * 2),
+                &ray,
+                |b, ray| b.iter(|| nearest_triangle(&mesh, black_box(*ray))),
+            );
+        }
+    }
+    group.finish();
+}
+
+/// `count` x `count` grid of spheres in the z = 0 plane, 3 units apart, the first at the origin
+/// In an app with `MouseRayPlugin`, updated once so `cast_ray` gets the mesh bounds the plugin
+/// builds, as it would in a game
+fn scene(sectors: usize, stacks: usize, count: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), TransformPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<Image>()
+        .init_asset::<StandardMaterial>()
+        .init_resource::<Input<MouseButton>>()
+        .init_resource::<Input<KeyCode>>()
+        .add_event::<CursorMoved>()
+        .add_event::<WindowFocused>()
+        .add_plugins(MouseRayPlugin);
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(sphere(sectors, stacks));
+    for x in 0..count {
+        for y in 0..count {
+            let transform = Transform::from_xyz(x as f32 * 3.0, y as f32 * 3.0, 0.0);
+            app.world.spawn((
+                mesh.clone(),
+                SpatialBundle::from_transform(transform),
+                Hoverable,
+            ));
+        }
+    }
+    app.update();
+    app
+}
+
+fn scene_rays(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cast_ray");
+    for (sectors, stacks) in SIZES {
+        let mut app = scene(sectors, stacks, 10);
+        for (name, ray) in rays() {
+            group.bench_with_input(
+                BenchmarkId::new(name, sectors * stacks * 2),
+                &ray,
+                |b, ray| b.iter(|| cast_ray(&mut app.world, black_box(*ray))),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, triangles, scene_rays);
+criterion_main!(benches);
@@ -7,6 +7,7 @@ use std::time::Duration;
 use bevy::asset::AssetMetaCheck;
 
 use bevy_hover as hover;
+use hover::map::MapRange;
 
 #[derive(Component)]
 struct SphereSeg {
@@ -88,7 +89,7 @@ fn setup(
                 ..default()
             },
         ))
-        .insert(hover::MouseRaySource);
+        .insert(hover::RaySource::Mouse);
 }
 
 fn on_hover(
@@ -177,35 +178,6 @@ fn main() {
         .run();
 }
 
-pub trait MapRange {
-    type Num;
-    fn map(&self, src: (Self::Num, Self::Num), dst: (Self::Num, Self::Num)) -> Self::Num;
-    fn map_clamped(&self, src: (Self::Num, Self::Num), dst: (Self::Num, Self::Num)) -> Self::Num;
-}
-
-impl MapRange for f32 {
-    type Num = f32;
-    fn map(&self, src: (f32, f32), dst: (f32, f32)) -> f32 {
-        if src.0 == src.1 {
-            return dst.0; // avoid div by 0
-        }
-        let m = (dst.1 - dst.0) / (src.1 - src.0);
-        let b = ((dst.0 * src.1) - (dst.1 * src.0)) / (src.1 - src.0);
-        // y = mx+b
-        (self * m) + b
-    }
-    fn map_clamped(&self, src: (f32, f32), dst: (f32, f32)) -> f32 {
-        let clamped = if src.0 <= src.1 {
-            self.clamp(src.0, src.1)
-        } else {
-            self.clamp(src.1, src.0)
-        };
-
-        clamped.map(src, dst)
-    }
-}
-
-
 #[derive(Resource)]
 struct SphereRotVel {
     pub vel: Quat, // Sphere rotates by the `vel` quat each second
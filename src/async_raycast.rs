@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::Face;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
+use bevy::utils::HashMap;
+use std::sync::Arc;
+
+use crate::colliders::LocalCollider;
+use crate::{
+    HoverIntersect, Hoverable, IgnoredEntities, PickShape, PickTarget, PickingSettings, RayHit,
+    ScreenTolerance,
+};
+
+/// Runs a ray-vs-scene query on the `AsyncComputeTaskPool`, for queries too expensive to run on
+/// the main thread (e.g. high-poly scenes)
+/// Spawn an entity with this component, once the query finishes the entity gets an
+/// `AsyncRaycastResult` with the nearest `Hoverable` hit
+/// The query runs against a snapshot of the hoverables taken the frame it was spawned,
+/// anything that moves afterwards won't be reflected in the result
+#[derive(Component, Debug)]
+pub struct AsyncRaycast {
+    pub ray: Ray,
+}
+
+/// Inserted next to `AsyncRaycast` when its query completes
+#[derive(Component, Debug)]
+pub struct AsyncRaycastResult {
+    /// nearest hoverable along the ray
+    pub hit: Option<RayHit>,
+}
+
+#[derive(Component)]
+pub(crate) struct AsyncRaycastTask(Task<Option<RayHit>>);
+
+/// Meshes are cloned, so the task owns everything it reads
+struct Snapshot {
+    meshes: HashMap<AssetId<Mesh>, Mesh>,
+    targets: Vec<SnapshotTarget>,
+    settings: PickingSettings,
+}
+
+struct SnapshotTarget {
+    entity: Entity,
+    transform: GlobalTransform,
+    mesh: Option<AssetId<Mesh>>,
+    intersector: Option<HoverIntersect>,
+    collider: Option<Box<dyn LocalCollider>>,
+    cull_mode: Option<Option<Face>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_async_raycasts(
+    mut commands: Commands,
+    mesh_assets: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    settings: Res<PickingSettings>,
+    requests: Query<(Entity, &AsyncRaycast), Added<AsyncRaycast>>,
+    query: Query<PickTarget, With<Hoverable>>,
+    parents: Query<&GlobalTransform>,
+    ignored: Res<IgnoredEntities>,
+) {
+    let parent_of = |parent| parents.get(parent).ok().copied();
+    if requests.is_empty() {
+        return;
+    }
+
+    // one snapshot, shared by all queries spawned this frame
+    // each distinct mesh is cloned once, no matter how many entities use it
+    let mut snapshot = Snapshot {
+        meshes: HashMap::new(),
+        targets: Vec::new(),
+        settings: settings.clone(),
+    };
+    for target in query
+        .iter()
+        .filter(|target| !ignored.0.contains(&target.entity))
+    {
+        // children of a parent without a `GlobalTransform`, see `PickTargetItem::global_transform`
+        let Some(transform) = target.global_transform(&parent_of) else {
+            continue;
+        };
+        let mesh = target.mesh_handle().and_then(|mesh_handle| {
+            let mesh = mesh_assets.get(mesh_handle)?;
+            snapshot
+                .meshes
+                .entry(mesh_handle.id())
+                .or_insert_with(|| mesh.clone());
+            Some(mesh_handle.id())
+        });
+        snapshot.targets.push(SnapshotTarget {
+            entity: target.entity,
+            transform,
+            mesh,
+            intersector: target.intersector.cloned(),
+            collider: target.collider().map(|collider| collider.boxed_clone()),
+            cull_mode: target
+                .shape(&mesh_assets, None, Some(&materials), &parent_of)
+                .cull_mode,
+        });
+    }
+    let snapshot = Arc::new(snapshot);
+
+    let pool = AsyncComputeTaskPool::get();
+    for (request, raycast) in requests.iter() {
+        let snapshot = snapshot.clone();
+        let ray = raycast.ray;
+        let task = pool.spawn(async move {
+            let mut nearest: Option<RayHit> = None;
+            for target in &snapshot.targets {
+                let shape = PickShape {
+                    transform: target.transform,
+                    mesh: target.mesh.and_then(|id| snapshot.meshes.get(&id)),
+                    bounds: None,
+                    cpu_mesh: None,
+                    intersector: target.intersector.as_ref().map(|intersect| &*intersect.0),
+                    collider: target.collider.as_deref(),
+                    cull_mode: target.cull_mode,
+                };
+                let hit = shape
+                    .intersect(&ray, &snapshot.settings, ScreenTolerance::default())
+                    .map(|hit| hit.into_ray_hit(target.entity, &ray));
+                match (hit, nearest) {
+                    (Some(hit), Some(n)) if n.distance > hit.distance => nearest = Some(hit),
+                    (Some(hit), None) => nearest = Some(hit),
+                    _ => (),
+                }
+            }
+            nearest
+        });
+        commands.entity(request).insert(AsyncRaycastTask(task));
+    }
+}
+
+pub(crate) fn poll_async_raycasts(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut AsyncRaycastTask)>,
+) {
+    for (entity, mut task) in tasks.iter_mut() {
+        if task.0.is_finished() {
+            // already finished, so this doesn't actually block
+            let hit = block_on(&mut task.0);
+            commands
+                .entity(entity)
+                .remove::<AsyncRaycastTask>()
+                .insert(AsyncRaycastResult { hit });
+        }
+    }
+}
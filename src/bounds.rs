@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy::utils::{HashMap, HashSet};
+
+use crate::colliders::slab_test;
+use crate::{
+    mesh_triangle_indices, unpickable_reason, vertex_positions, Hoverable, Occluder, PickTarget,
+    PickingStats,
+};
+
+/// Model space bounding box of a mesh
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ModelBounds {
+    min: Vec3,
+    max: Vec3,
+}
+
+impl ModelBounds {
+    fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        let positions = vertex_positions(mesh)?;
+        let mut points = positions.iter().copied().map(Vec3::from);
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+        Some(Self { min, max })
+    }
+
+    pub(crate) fn to_aabb(self) -> Aabb {
+        Aabb::from_min_max(self.min, self.max)
+    }
+
+    /// false if the model space ray can't hit anything inside the box
+    pub(crate) fn intersects(&self, origin: Vec3, direction: Vec3) -> bool {
+        self.entry(origin, direction).is_some()
+    }
+
+    /// Where the model space ray enters the box, in multiples of `direction`
+    /// 0 if it starts inside, None if it misses
+    pub(crate) fn entry(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        slab_test(origin, direction, self.min, self.max).map(|(t_enter, _)| t_enter.max(0.0))
+    }
+}
+
+/// Just what picking reads of a triangle mesh, packed tightly: positions, and the indices of
+/// every triangle (strips unrolled into a list)
+pub(crate) struct CpuMesh {
+    pub(crate) positions: Vec<[f32; 3]>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl CpuMesh {
+    /// None for meshes without triangles
+    fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        Some(Self {
+            positions: vertex_positions(mesh)?.into_owned(),
+            indices: mesh_triangle_indices(mesh)?
+                .flatten()
+                .map(|index| index as u32)
+                .collect(),
+        })
+    }
+
+    pub(crate) fn triangles(&self) -> Box<dyn Iterator<Item = [usize; 3]> + '_> {
+        Box::new(
+            self.indices
+                .chunks_exact(3)
+                .map(|tri| [tri[0] as usize, tri[1] as usize, tri[2] as usize]),
+        )
+    }
+}
+
+/// Bounds and `CpuMesh` of every mesh that's picked against, keyed by mesh rather than by entity
+/// Many entities sharing one mesh (e.g. instanced props) share one entry: it's built once, and
+/// each entity only moves the ray into its own model space to test against it
+#[derive(Resource, Default)]
+pub(crate) struct MeshCache {
+    bounds: HashMap<AssetId<Mesh>, ModelBounds>,
+    meshes: HashMap<AssetId<Mesh>, CpuMesh>,
+    /// meshes already reported as unpickable, so each is only reported once
+    warned: HashSet<AssetId<Mesh>>,
+}
+
+impl MeshCache {
+    pub(crate) fn get(&self, mesh: AssetId<Mesh>) -> Option<ModelBounds> {
+        self.bounds.get(&mesh).copied()
+    }
+
+    pub(crate) fn cpu_mesh(&self, mesh: AssetId<Mesh>) -> Option<&CpuMesh> {
+        self.meshes.get(&mesh)
+    }
+}
+
+/// Builds bounds and `CpuMesh`es for newly picked meshes, and drops those of edited or unloaded
+/// ones
+/// Also warns (once per mesh) about meshes that can't be picked, which would otherwise just
+/// never be hovered without any explanation
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_mesh_bounds(
+    mut cache: ResMut<MeshCache>,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mesh_assets: Res<Assets<Mesh>>,
+    targets: Query<PickTarget, Or<(With<Hoverable>, With<Occluder>)>>,
+    mut stats: ResMut<PickingStats>,
+) {
+    stats.meshes_built = 0;
+    for event in mesh_events.read() {
+        match event {
+            AssetEvent::Modified { id } | AssetEvent::Removed { id } => {
+                cache.bounds.remove(id);
+                cache.meshes.remove(id);
+                // an edit may have fixed (or broken) it
+                cache.warned.remove(id);
+            }
+            _ => (),
+        }
+    }
+
+    for target in targets.iter() {
+        let Some(handle) = target.mesh_handle() else {
+            continue;
+        };
+        if cache.bounds.contains_key(&handle.id()) {
+            continue;
+        }
+        // meshes that haven't loaded yet are retried next frame
+        let Some(mesh) = mesh_assets.get(handle) else {
+            continue;
+        };
+        if let Some(bounds) = ModelBounds::from_mesh(mesh) {
+            cache.bounds.insert(handle.id(), bounds);
+            stats.meshes_built += 1;
+        }
+        if let Some(cpu_mesh) = CpuMesh::from_mesh(mesh) {
+            cache.meshes.insert(handle.id(), cpu_mesh);
+        }
+        // a custom intersector or collider replaces the mesh, it doesn't matter then
+        let mesh_used = target.intersector.is_none() && target.collider().is_none();
+        if mesh_used && !cache.warned.contains(&handle.id()) {
+            if let Some(reason) = unpickable_reason(mesh) {
+                warn!(
+                    "bevy-hover: {:?} can't be picked, {reason}: mesh {:?}",
+                    target.entity,
+                    handle.id()
+                );
+                cache.warned.insert(handle.id());
+            }
+        }
+    }
+}
+
+/// Marks an `Aabb` inserted by `insert_missing_aabbs`, so it's kept up to date with the mesh
+#[derive(Component)]
+pub(crate) struct HoverAabb;
+
+/// Gives hoverables whose mesh didn't get an `Aabb` from bevy (e.g. spawned without the render
+/// plugins, or with `NoFrustumCulling`) one computed from their mesh
+/// Those are recomputed when their mesh changes, bevy's own ones are left alone
+#[allow(clippy::type_complexity)]
+pub(crate) fn insert_missing_aabbs(
+    mut commands: Commands,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mesh_assets: Res<Assets<Mesh>>,
+    missing: Query<(Entity, &Handle<Mesh>), (With<Hoverable>, Without<Aabb>)>,
+    mut ours: Query<(Ref<Handle<Mesh>>, &mut Aabb), With<HoverAabb>>,
+) {
+    for (entity, handle) in missing.iter() {
+        // meshes that haven't loaded yet are retried next frame
+        if let Some(bounds) = mesh_assets.get(handle).and_then(ModelBounds::from_mesh) {
+            commands
+                .entity(entity)
+                .insert((bounds.to_aabb(), HoverAabb));
+        }
+    }
+
+    let modified: HashSet<AssetId<Mesh>> = mesh_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for (handle, mut aabb) in ours.iter_mut() {
+        if !(handle.is_changed() || modified.contains(&handle.id())) {
+            continue;
+        }
+        if let Some(bounds) = mesh_assets.get(&*handle).and_then(ModelBounds::from_mesh) {
+            *aabb = bounds.to_aabb();
+        }
+    }
+}
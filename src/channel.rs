@@ -0,0 +1,36 @@
+use async_channel::{Receiver, Sender};
+use bevy::prelude::*;
+
+use crate::HoverChanged;
+
+/// Every `HoverChanged`, forwarded into a channel for async code (e.g. an async UI runtime)
+/// Inserted by `MouseRayPlugin` with the `channel` feature, await on a clone of `receiver()`
+/// The channel is unbounded and keeps events until they're received, so drain it
+#[derive(Resource, Clone)]
+pub struct HoverChangedChannel {
+    sender: Sender<HoverChanged>,
+    receiver: Receiver<HoverChanged>,
+}
+
+impl Default for HoverChangedChannel {
+    fn default() -> Self {
+        let (sender, receiver) = async_channel::unbounded();
+        Self { sender, receiver }
+    }
+}
+
+impl HoverChangedChannel {
+    pub fn receiver(&self) -> Receiver<HoverChanged> {
+        self.receiver.clone()
+    }
+}
+
+pub(crate) fn forward_hover_changed(
+    channel: Res<HoverChangedChannel>,
+    mut ev_hover_changed: EventReader<HoverChanged>,
+) {
+    for event in ev_hover_changed.read() {
+        // can't fail, the channel holds on to a receiver itself
+        let _ = channel.sender.try_send(*event);
+    }
+}
@@ -0,0 +1,391 @@
+//! Analytic collider components, picked without triangle tests
+//! Each shape is defined in its entity's local space: the ray is moved into that space with the
+//! inverse `GlobalTransform`, so colliders rotate, scale and move with their entity
+
+use bevy::prelude::*;
+
+/// A shape that can be tested directly against a ray in its entity's local space
+pub(crate) trait LocalCollider: Send + Sync + 'static {
+    /// Distance along the local ray, in multiples of `direction`, where it first hits the
+    /// shape's surface, None for a miss
+    /// `direction` is not necessarily normalized
+    fn intersect_local(&self, origin: Vec3, direction: Vec3) -> Option<f32>;
+
+    /// Owned copy, for snapshots handed to other threads
+    fn boxed_clone(&self) -> Box<dyn LocalCollider>;
+}
+
+/// Oriented box collider, centered on the entity
+/// `half_extents` are along the entity's local axes, before its scale
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HoverObb {
+    pub half_extents: Vec3,
+}
+
+impl LocalCollider for HoverObb {
+    fn intersect_local(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        let (t_enter, t_exit) =
+            slab_test(origin, direction, -self.half_extents, self.half_extents)?;
+        // a ray starting inside the box first hits its surface on the way out
+        Some(if t_enter >= 0.0 { t_enter } else { t_exit })
+    }
+
+    fn boxed_clone(&self) -> Box<dyn LocalCollider> {
+        Box::new(*self)
+    }
+}
+
+/// Capsule collider: a cylinder along the entity's local Y axis with hemispherical caps
+/// `half_height` is the distance from the center to either cap's center, so the capsule's total
+/// height is `2 * (half_height + radius)`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HoverCapsule {
+    pub radius: f32,
+    pub half_height: f32,
+}
+
+impl LocalCollider for HoverCapsule {
+    fn intersect_local(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        // the quadratics below assume a unit direction
+        // solve with one, then scale the result back into multiples of `direction`
+        let len = direction.length();
+        if len == 0.0 {
+            return None;
+        }
+        let dir = direction / len;
+
+        // a capsule is the union of a cylinder and a sphere at each end
+        // the ray enters the union where it first enters any of them
+        // (the cylinder's flat ends are inside the spheres, so only its side needs testing)
+        let top = Vec3::Y * self.half_height;
+        let body = cylinder_side_hit(origin, dir, self.radius, self.half_height);
+        let caps = [-top, top].map(|center| sphere_hit(origin, dir, center, self.radius));
+        [body, caps[0], caps[1]]
+            .into_iter()
+            .flatten()
+            .min_by(f32::total_cmp)
+            .map(|t| t / len)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn LocalCollider> {
+        Box::new(*self)
+    }
+}
+
+/// Convex collider, the intersection of half-spaces
+/// Each plane is `(normal, w)` with an outward `normal`: points `p` with `normal.dot(p) + w <= 0`
+/// are inside, all in the entity's local space
+#[derive(Component, Debug, Clone, Default)]
+pub struct HoverConvexHull {
+    pub planes: Vec<Vec4>,
+}
+
+impl HoverConvexHull {
+    /// Hull planes from the triangles of a convex `mesh`, in the mesh's model space
+    /// Every face plane of a convex mesh is a plane of its hull, so this is only correct for
+    /// convex meshes: a concave mesh gives the intersection of its face half-spaces instead
+    /// None if the mesh has no triangles to read
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        let mut planes: Vec<Vec4> = Vec::new();
+        for [v0, v1, v2] in crate::mesh_triangle_iter(mesh)? {
+            // counter-clockwise winding faces outward
+            let Some(normal) = (v1 - v0).cross(v2 - v0).try_normalize() else {
+                continue; // degenerate triangle
+            };
+            let plane = normal.extend(-normal.dot(v0));
+            // coplanar triangles (e.g. the 2 halves of a quad) share a plane
+            if !planes.iter().any(|p| p.abs_diff_eq(plane, 1e-5)) {
+                planes.push(plane);
+            }
+        }
+        (!planes.is_empty()).then_some(Self { planes })
+    }
+}
+
+impl LocalCollider for HoverConvexHull {
+    fn intersect_local(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        // clip the ray against each half-space, like the slab test in `HoverObb` but with
+        // arbitrary planes: the ray enters through the last plane it crosses inward, and
+        // leaves through the first plane it crosses outward
+        let mut t_enter = f32::NEG_INFINITY;
+        let mut t_exit = f32::INFINITY;
+        for plane in &self.planes {
+            let normal = plane.truncate();
+            let denom = normal.dot(direction);
+            let dist = normal.dot(origin) + plane.w;
+            if denom == 0.0 {
+                // parallel to the plane: entirely inside or entirely outside it
+                if dist > 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let t = -dist / denom;
+            if denom < 0.0 {
+                t_enter = t_enter.max(t);
+            } else {
+                t_exit = t_exit.min(t);
+            }
+        }
+
+        if self.planes.is_empty() || t_exit < t_enter.max(0.0) {
+            return None;
+        }
+        Some(if t_enter >= 0.0 { t_enter } else { t_exit })
+    }
+
+    fn boxed_clone(&self) -> Box<dyn LocalCollider> {
+        Box::new(self.clone())
+    }
+}
+
+/// Plane collider through the entity's origin, for floors, walls and tabletops
+/// Unbounded if `half_size` is None, otherwise a rectangle centered on the entity's origin
+/// The rectangle's first axis is the entity's local X axis projected onto the plane (local Z
+/// if the normal is along X), the second is perpendicular to it: for a `Vec3::Y` normal,
+/// `half_size` is the rectangle's half extents along X and Z
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HoverPlane {
+    pub normal: Vec3,
+    pub half_size: Option<Vec2>,
+}
+
+impl HoverPlane {
+    /// Unbounded plane
+    pub fn infinite(normal: Vec3) -> Self {
+        Self {
+            normal,
+            half_size: None,
+        }
+    }
+
+    /// Rectangle with half extents `half_size`
+    pub fn bounded(normal: Vec3, half_size: Vec2) -> Self {
+        Self {
+            normal,
+            half_size: Some(half_size),
+        }
+    }
+
+    /// In-plane axes the rectangle's extents are measured along
+    fn axes(&self) -> Option<(Vec3, Vec3)> {
+        let normal = self.normal.try_normalize()?;
+        let reference = if normal.x.abs() > 0.999 {
+            Vec3::Z
+        } else {
+            Vec3::X
+        };
+        let u = reference.reject_from_normalized(normal).normalize();
+        Some((u, normal.cross(u)))
+    }
+}
+
+impl LocalCollider for HoverPlane {
+    fn intersect_local(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        let t = crate::pick_plane(origin, direction, Vec3::ZERO, self.normal)?;
+        let Some(half_size) = self.half_size else {
+            return Some(t);
+        };
+        let (u, v) = self.axes()?;
+        let hit = origin + direction * t;
+        let inside = hit.dot(u).abs() <= half_size.x && hit.dot(v).abs() <= half_size.y;
+        inside.then_some(t)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn LocalCollider> {
+        Box::new(*self)
+    }
+}
+
+/// Terrain collider: a grid of heights in the entity's local XZ plane, centered on its origin
+/// `heights` is stored row by row, `heights[z * columns + x]` is the height of the vertex
+/// `x` cells along X and `z` cells along Z; neighbouring vertices are `cell_size` apart
+/// Each cell is split into 2 triangles along its diagonal from (x, z) to (x + 1, z + 1), the
+/// same surface `height_at` interpolates
+#[derive(Component, Debug, Clone)]
+pub struct HoverHeightfield {
+    pub heights: Vec<f32>,
+    /// Vertices per row, at least 2
+    pub columns: usize,
+    pub cell_size: Vec2,
+}
+
+impl HoverHeightfield {
+    /// Vertices per column
+    pub fn rows(&self) -> usize {
+        self.heights.len() / self.columns.max(1)
+    }
+
+    /// Local position of the vertex at the grid's (0, 0) corner, with height 0
+    fn corner(&self) -> Vec2 {
+        let cells = Vec2::new(self.columns as f32 - 1.0, self.rows() as f32 - 1.0);
+        -cells * self.cell_size / 2.0
+    }
+
+    fn height(&self, x: usize, z: usize) -> f32 {
+        self.heights[z * self.columns + x]
+    }
+
+    fn vertex(&self, x: usize, z: usize) -> Vec3 {
+        let xz = self.corner() + Vec2::new(x as f32, z as f32) * self.cell_size;
+        Vec3::new(xz.x, self.height(x, z), xz.y)
+    }
+
+    /// Height of the terrain surface above the local point (`xz.x`, `xz.y`), None outside the grid
+    pub fn height_at(&self, xz: Vec2) -> Option<f32> {
+        if self.columns < 2 || self.rows() < 2 {
+            return None;
+        }
+        let grid = (xz - self.corner()) / self.cell_size;
+        let max = Vec2::new(self.columns as f32 - 1.0, self.rows() as f32 - 1.0);
+        if grid.cmplt(Vec2::ZERO).any() || grid.cmpgt(max).any() {
+            return None;
+        }
+        // the far edges belong to the last cell
+        let cell = grid.floor().min(max - 1.0);
+        let (x, z) = (cell.x as usize, cell.y as usize);
+        let f = grid - cell;
+        let h00 = self.height(x, z);
+        let h11 = self.height(x + 1, z + 1);
+        Some(if f.x >= f.y {
+            let h10 = self.height(x + 1, z);
+            h00 + f.x * (h10 - h00) + f.y * (h11 - h10)
+        } else {
+            let h01 = self.height(x, z + 1);
+            h00 + f.y * (h01 - h00) + f.x * (h11 - h01)
+        })
+    }
+
+    /// Nearest hit with the 2 triangles of the cell at (x, z)
+    fn cell_hit(&self, origin: Vec3, direction: Vec3, x: usize, z: usize) -> Option<f32> {
+        let v00 = self.vertex(x, z);
+        let v10 = self.vertex(x + 1, z);
+        let v01 = self.vertex(x, z + 1);
+        let v11 = self.vertex(x + 1, z + 1);
+        [[v00, v11, v10], [v00, v01, v11]]
+            .into_iter()
+            .filter_map(|[a, b, c]| {
+                crate::moller_trumbore(origin, direction, a, b, c, f32::EPSILON, 0.0)
+            })
+            .min_by(f32::total_cmp)
+    }
+}
+
+impl LocalCollider for HoverHeightfield {
+    fn intersect_local(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        let (columns, rows) = (self.columns, self.rows());
+        if columns < 2 || rows < 2 {
+            return None;
+        }
+
+        // only the part of the ray inside the terrain's bounding box can hit it
+        let corner = self.corner();
+        let (min_height, max_height) = self
+            .heights
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), h| {
+                (lo.min(*h), hi.max(*h))
+            });
+        let min = Vec3::new(corner.x, min_height, corner.y);
+        let max = Vec3::new(-corner.x, max_height, -corner.y);
+        let (t_enter, t_exit) = slab_test(origin, direction, min, max)?;
+        let t_enter = t_enter.max(0.0);
+
+        // walk the cells under the ray in order (2D DDA in grid units), so the first cell with
+        // a hit has the nearest one
+        let start = (origin.xz() - corner) / self.cell_size;
+        let step = direction.xz() / self.cell_size;
+        let last_cell = IVec2::new(columns as i32 - 2, rows as i32 - 2);
+        let mut cell = (start + step * t_enter)
+            .floor()
+            .as_ivec2()
+            .clamp(IVec2::ZERO, last_cell);
+        // ray distance to the next cell boundary along each axis, and between boundaries
+        let next_boundary = |cell: i32, start: f32, step: f32| {
+            let edge = if step > 0.0 { cell + 1 } else { cell };
+            if step == 0.0 {
+                f32::INFINITY
+            } else {
+                (edge as f32 - start) / step
+            }
+        };
+        let mut t_next = Vec2::new(
+            next_boundary(cell.x, start.x, step.x),
+            next_boundary(cell.y, start.y, step.y),
+        );
+        let t_delta = step.abs().recip();
+        let cell_step = IVec2::new(step.x.signum() as i32, step.y.signum() as i32);
+
+        loop {
+            if let Some(t) = self.cell_hit(origin, direction, cell.x as usize, cell.y as usize) {
+                return Some(t);
+            }
+            let axis = if t_next.x < t_next.y { 0 } else { 1 };
+            if t_next[axis] > t_exit {
+                return None;
+            }
+            cell[axis] += cell_step[axis];
+            t_next[axis] += t_delta[axis];
+            if cell.cmplt(IVec2::ZERO).any() || cell.cmpgt(last_cell).any() {
+                return None;
+            }
+        }
+    }
+
+    fn boxed_clone(&self) -> Box<dyn LocalCollider> {
+        Box::new(self.clone())
+    }
+}
+
+/// Slab test: clips the ray against the 3 pairs of parallel planes of the axis-aligned box from
+/// `min` to `max`, the ray is inside the box where it's between all 3 pairs at once
+/// The entry and exit distance, in multiples of `direction`, None if the ray misses the box or
+/// the box is entirely behind the origin
+pub(crate) fn slab_test(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<(f32, f32)> {
+    // division by a zero component gives +-inf, which the min/max below handle
+    let inv = direction.recip();
+    let t1 = (min - origin) * inv;
+    let t2 = (max - origin) * inv;
+    let t_enter = t1.min(t2).max_element();
+    let t_exit = t1.max(t2).min_element();
+
+    if t_exit < t_enter.max(0.0) {
+        return None;
+    }
+    Some((t_enter, t_exit))
+}
+
+/// First hit, in front of `origin`, of a unit-direction ray with a sphere
+fn sphere_hit(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let oc = origin - center;
+    let b = oc.dot(dir);
+    let c = oc.length_squared() - radius * radius;
+    let h = b * b - c;
+    if h < 0.0 {
+        return None;
+    }
+    let h = h.sqrt();
+    // the near root is behind the origin if the origin is inside the sphere
+    [-b - h, -b + h].into_iter().find(|t| *t >= 0.0)
+}
+
+/// First hit, in front of `origin`, of a unit-direction ray with the side of a Y-aligned,
+/// origin-centered cylinder, between y = -half_height and y = half_height
+fn cylinder_side_hit(origin: Vec3, dir: Vec3, radius: f32, half_height: f32) -> Option<f32> {
+    // circle test in the XZ plane
+    let (o, d) = (origin.xz(), dir.xz());
+    let a = d.length_squared();
+    if a == 0.0 {
+        return None; // parallel to the axis, never crosses the side
+    }
+    let b = o.dot(d);
+    let c = o.length_squared() - radius * radius;
+    let h = b * b - a * c;
+    if h < 0.0 {
+        return None;
+    }
+    let h = h.sqrt();
+    [(-b - h) / a, (-b + h) / a]
+        .into_iter()
+        .find(|t| *t >= 0.0 && (origin.y + dir.y * t).abs() <= half_height)
+}
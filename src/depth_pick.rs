@@ -0,0 +1,290 @@
+//! `PickingBackend::Depth`: copies the depth under the cursor out of the camera's depth prepass,
+//! back in the main world it's unprojected to the surface point and the hoverable whose bounds
+//! hold that point is hovered. No triangles are tested, the renderer already found the surface
+
+use bevy::asset::load_internal_asset;
+use bevy::core_pipeline::core_3d;
+use bevy::core_pipeline::prepass::ViewPrepassTextures;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::render_graph::{
+    NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner,
+};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::{Render, RenderApp, RenderSet};
+
+use crate::readback::{
+    Hoverables, PendingReadbacks, ReadbackCursor, ReadbackRequest, READBACK_SIZE,
+};
+use crate::{PickingBackend, ReadbackHit};
+
+const DEPTH_PICK_SHADER: Handle<Shader> =
+    Handle::weak_from_u128(0x2d84_95e1_c7a0_4b3f_9f16_58e3_a4c2_0d7b);
+
+/// Render graph node copying the depth out, between the prepasses and the main pass
+const DEPTH_PICK: &str = "bevy_hover_depth_pick";
+
+/// How far off a hoverable's bounds the surface point may be and still pick it, relative to
+/// the point's distance from the near plane, for depth precision
+const BOUNDS_TOLERANCE: f32 = 0.01;
+
+pub(crate) fn build(app: &mut App) {
+    // headless, without the renderer there's no depth to read, picking stays on the CPU
+    if app.get_sub_app(RenderApp).is_err() {
+        return;
+    }
+    load_internal_asset!(app, DEPTH_PICK_SHADER, "depth_pick.wgsl", Shader::from_wgsl);
+    app.sub_app_mut(RenderApp)
+        .init_resource::<DepthPickState>()
+        .init_resource::<SpecializedComputePipelines<DepthPickPipeline>>()
+        .add_systems(Render, prepare_depth_pick.in_set(RenderSet::Prepare))
+        .add_render_graph_node::<ViewNodeRunner<DepthPickNode>>(core_3d::graph::NAME, DEPTH_PICK)
+        .add_render_graph_edges(
+            core_3d::graph::NAME,
+            &[
+                core_3d::graph::node::END_PREPASSES,
+                DEPTH_PICK,
+                core_3d::graph::node::START_MAIN_PASS,
+            ],
+        );
+}
+
+pub(crate) fn finish(app: &mut App) {
+    if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app.init_resource::<DepthPickPipeline>();
+    }
+}
+
+/// The hoverable at the depth `words` read under `cursor`, see `depth_pick.wgsl`
+/// Nothing for the cleared depth, or a surface that isn't in any hoverable's bounds, like a
+/// floor that isn't hoverable
+pub(crate) fn decode(
+    words: [u32; 2],
+    cursor: ReadbackCursor,
+    hoverables: &Hoverables,
+) -> ReadbackHit {
+    let depth = f32::from_bits(words[0]);
+    // cleared to 0, the far plane with bevy's reverse z, nothing was drawn there
+    if depth <= 0.0 {
+        return ReadbackHit {
+            entity: None,
+            point: None,
+        };
+    }
+    let world_from_clip = cursor.clip_from_world.inverse();
+    let point = world_from_clip.project_point3(cursor.ndc.extend(depth));
+    let near = world_from_clip.project_point3(cursor.ndc.extend(1.0));
+    let tolerance = BOUNDS_TOLERANCE * point.distance(near);
+    let entity = hoverables
+        .iter()
+        .map(|(entity, aabb, transform)| {
+            // the closest point of the bounds, in world space so scale doesn't skew distances
+            let local = transform.affine().inverse().transform_point3(point);
+            let closest = local.clamp(aabb.min().into(), aabb.max().into());
+            (transform.transform_point(closest).distance(point), entity)
+        })
+        .filter(|(distance, _)| *distance <= tolerance)
+        .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+        .map(|(_, entity)| entity);
+    ReadbackHit {
+        entity,
+        point: entity.map(|_| point),
+    }
+}
+
+#[derive(Resource)]
+struct DepthPickPipeline {
+    /// for a single sampled and a multisampled depth texture
+    layouts: [BindGroupLayout; 2],
+    /// the pixel to read, written each frame there's a cursor
+    pixel_buffer: Buffer,
+    /// the depth the shader read, copied into a readback buffer
+    depth_buffer: Buffer,
+}
+
+impl DepthPickPipeline {
+    fn layout(&self, multisampled: bool) -> &BindGroupLayout {
+        &self.layouts[usize::from(multisampled)]
+    }
+}
+
+impl FromWorld for DepthPickPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = |multisampled| {
+            render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("bevy_hover_depth_pick_layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Depth,
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            })
+        };
+        Self {
+            layouts: [layout(false), layout(true)],
+            pixel_buffer: render_device.create_buffer(&BufferDescriptor {
+                label: Some("bevy_hover_depth_pick_pixel"),
+                size: 16,
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            depth_buffer: render_device.create_buffer(&BufferDescriptor {
+                label: Some("bevy_hover_depth_pick_depth"),
+                size: READBACK_SIZE,
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+        }
+    }
+}
+
+impl SpecializedComputePipeline for DepthPickPipeline {
+    /// multisampled
+    type Key = bool;
+
+    fn specialize(&self, multisampled: Self::Key) -> ComputePipelineDescriptor {
+        ComputePipelineDescriptor {
+            label: Some("bevy_hover_depth_pick_pipeline".into()),
+            layout: vec![self.layout(multisampled).clone()],
+            push_constant_ranges: Vec::new(),
+            shader: DEPTH_PICK_SHADER,
+            shader_defs: if multisampled {
+                vec!["MULTISAMPLED".into()]
+            } else {
+                Vec::new()
+            },
+            entry_point: "read_depth".into(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct DepthPickState {
+    cursor: Option<ReadbackCursor>,
+    /// pipeline for the camera's depth texture, and whether it's multisampled
+    pipeline: Option<(CachedComputePipelineId, bool)>,
+}
+
+fn prepare_depth_pick(
+    render_queue: Res<RenderQueue>,
+    pipeline_cache: Res<PipelineCache>,
+    pipeline: Res<DepthPickPipeline>,
+    mut pipelines: ResMut<SpecializedComputePipelines<DepthPickPipeline>>,
+    request: Res<ReadbackRequest>,
+    views: Query<&ViewPrepassTextures>,
+    mut state: ResMut<DepthPickState>,
+) {
+    state.cursor = request
+        .0
+        .filter(|cursor| cursor.backend == PickingBackend::Depth);
+    state.pipeline = state.cursor.and_then(|cursor| {
+        let depth = views.get(cursor.camera).ok()?.depth.as_ref()?;
+        let multisampled = depth.texture.sample_count() > 1;
+        // the viewport may have been resized since the cursor was
+        let size = UVec2::new(depth.texture.width(), depth.texture.height());
+        let pixel = cursor.pixel.min(size.saturating_sub(UVec2::ONE));
+        render_queue.write_buffer(
+            &pipeline.pixel_buffer,
+            0,
+            &[pixel.x, pixel.y, 0, 0].map(u32::to_le_bytes).concat(),
+        );
+        Some((
+            pipelines.specialize(&pipeline_cache, &pipeline, multisampled),
+            multisampled,
+        ))
+    });
+}
+
+#[derive(Default)]
+struct DepthPickNode;
+
+impl ViewNode for DepthPickNode {
+    type ViewQuery = &'static ViewPrepassTextures;
+
+    fn run<'w>(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        prepass_textures: QueryItem<'w, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let state = world.resource::<DepthPickState>();
+        let (Some(cursor), Some((pipeline_id, multisampled))) = (state.cursor, state.pipeline)
+        else {
+            return Ok(());
+        };
+        // every 3d camera runs the node, only the `RaySource::Mouse` one is read
+        if graph.view_entity() != cursor.camera {
+            return Ok(());
+        }
+        // still compiling, the last readback stands
+        let (Some(depth), Some(compute_pipeline)) = (
+            &prepass_textures.depth,
+            world
+                .resource::<PipelineCache>()
+                .get_compute_pipeline(pipeline_id),
+        ) else {
+            return Ok(());
+        };
+
+        let render_device = render_context.render_device();
+        let pipeline = world.resource::<DepthPickPipeline>();
+        let bind_group = render_device.create_bind_group(
+            "bevy_hover_depth_pick_bind_group",
+            pipeline.layout(multisampled),
+            &BindGroupEntries::sequential((
+                &depth.default_view,
+                pipeline.depth_buffer.as_entire_binding(),
+                pipeline.pixel_buffer.as_entire_binding(),
+            )),
+        );
+        // all the readback buffers are still being read, the last readback stands
+        let Some(readback) = world
+            .resource::<PendingReadbacks>()
+            .buffer(render_device, cursor)
+        else {
+            return Ok(());
+        };
+
+        let encoder = render_context.command_encoder();
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("bevy_hover_depth_pick_pass"),
+            });
+            pass.set_pipeline(compute_pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&pipeline.depth_buffer, 0, &readback, 0, READBACK_SIZE);
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+// Copies the prepass depth under the cursor into the readback, see `depth_pick.rs`
+
+#ifdef MULTISAMPLED
+@group(0) @binding(0) var depth_texture: texture_depth_multisampled_2d;
+#else
+@group(0) @binding(0) var depth_texture: texture_depth_2d;
+#endif
+// depth, then a word of padding to fill the readback
+@group(0) @binding(1) var<storage, read_write> depth_out: vec2<f32>;
+// x and y of the pixel under the cursor, padded to 16 bytes
+@group(0) @binding(2) var<uniform> pixel: vec4<u32>;
+
+@compute @workgroup_size(1)
+fn read_depth() {
+    // the first sample of a multisampled texture, the only mip level of one that isn't
+    depth_out = vec2<f32>(textureLoad(depth_texture, vec2<i32>(pixel.xy), 0), 0.0);
+}
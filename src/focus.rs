@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::{Hovered, RaySource};
+
+/// Can receive keyboard focus, see `Focused`
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Focusable;
+
+/// The focused `Focusable`, for driving the same interactions without a pointer
+/// Focus follows the hover onto any `Focusable`, and stays there when the cursor leaves
+/// Tab, Right and Down move it to the next `Focusable` in reading order on screen (top to
+/// bottom, then left to right), Shift+Tab, Left and Up to the previous one
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Focused(pub Option<Entity>);
+
+/// Sent whenever `Focused` changes
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FocusChanged {
+    pub previous: Option<Entity>,
+    pub current: Option<Entity>,
+}
+
+pub(crate) fn update_focus(
+    keys: Res<Input<KeyCode>>,
+    hovered: Res<Hovered>,
+    mut focused: ResMut<Focused>,
+    focusables: Query<(Entity, &GlobalTransform), With<Focusable>>,
+    cameras: Query<(&Camera, &GlobalTransform, &RaySource)>,
+    mut ev_focus_changed: EventWriter<FocusChanged>,
+) {
+    let previous = focused.0;
+    // a despawned entity, or one that stopped being focusable, loses focus
+    let mut current = previous.filter(|entity| focusables.contains(*entity));
+
+    if hovered.is_changed() {
+        if let Some(entity) = hovered.inner.filter(|entity| focusables.contains(*entity)) {
+            current = Some(entity);
+        }
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let step: isize = if keys.just_pressed(KeyCode::Tab) {
+        if shift {
+            -1
+        } else {
+            1
+        }
+    } else if keys.any_just_pressed([KeyCode::Right, KeyCode::Down]) {
+        1
+    } else if keys.any_just_pressed([KeyCode::Left, KeyCode::Up]) {
+        -1
+    } else {
+        0
+    };
+    if step != 0 {
+        let camera = cameras
+            .iter()
+            .find(|(.., source)| **source == RaySource::Mouse)
+            .map(|(camera, transform, _)| (camera, transform));
+        let order = reading_order(&focusables, camera);
+        current = match current.and_then(|entity| order.iter().position(|e| *e == entity)) {
+            Some(i) => {
+                let len = order.len() as isize;
+                Some(order[(i as isize + step).rem_euclid(len) as usize])
+            }
+            // nothing focused yet, start at either end
+            None if step > 0 => order.first().copied(),
+            None => order.last().copied(),
+        };
+    }
+
+    if current != previous {
+        focused.0 = current;
+        ev_focus_changed.send(FocusChanged { previous, current });
+    }
+}
+
+/// Focusables sorted top to bottom, then left to right, as seen from `camera`
+/// Without a camera, by world position instead (+Y is up)
+fn reading_order(
+    focusables: &Query<(Entity, &GlobalTransform), With<Focusable>>,
+    camera: Option<(&Camera, &GlobalTransform)>,
+) -> Vec<Entity> {
+    let mut positions: Vec<(Entity, Vec2)> = focusables
+        .iter()
+        .map(|(entity, transform)| {
+            let world = transform.translation();
+            let screen = camera
+                .and_then(|(camera, camera_transform)| camera.world_to_ndc(camera_transform, world))
+                .map_or(world.truncate(), |ndc| ndc.truncate());
+            (entity, screen)
+        })
+        .collect();
+    positions.sort_by(|(a, a_pos), (b, b_pos)| {
+        b_pos
+            .y
+            .total_cmp(&a_pos.y)
+            .then(a_pos.x.total_cmp(&b_pos.x))
+            // stable across frames for focusables at the same spot
+            .then(a.cmp(b))
+    });
+    positions.into_iter().map(|(entity, _)| entity).collect()
+}
@@ -0,0 +1,412 @@
+//! `PickingBackend::Gpu`: draws the id of every visible hoverable into a single pixel, with the
+//! camera's projection narrowed to the pixel under the cursor, then reads the id on top back
+//! Costs a draw per hoverable, but nothing per triangle on the CPU
+
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::main_graph::node::CAMERA_DRIVER;
+use bevy::render::mesh::{GpuBufferInfo, MeshVertexBufferLayout};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::{Extract, ExtractSchedule, Render, RenderApp, RenderSet};
+
+use crate::readback::{Hoverables, PendingReadbacks, ReadbackCursor, ReadbackRequest};
+use crate::{Hoverable, IgnoredEntities, PickingBackend, PickingSettings, ReadbackHit};
+
+const GPU_PICK_SHADER: Handle<Shader> =
+    Handle::weak_from_u128(0x6b1f_3c2a_9e4d_4f07_8a35_d2c1_b0e9_7f41);
+
+/// Render graph node drawing the ids
+const GPU_PICK: &str = "bevy_hover_gpu_pick";
+
+pub(crate) fn build(app: &mut App) {
+    // headless, without the renderer there's nothing to draw with, picking stays on the CPU
+    if app.get_sub_app(RenderApp).is_err() {
+        return;
+    }
+    load_internal_asset!(app, GPU_PICK_SHADER, "gpu_pick.wgsl", Shader::from_wgsl);
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app
+        .init_resource::<ExtractedPickMeshes>()
+        .init_resource::<GpuPickDraws>()
+        .init_resource::<SpecializedMeshPipelines<GpuPickPipeline>>()
+        .add_systems(ExtractSchedule, extract_pick_meshes)
+        .add_systems(Render, prepare_gpu_pick.in_set(RenderSet::Prepare));
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    graph.add_node(GPU_PICK, GpuPickNode);
+    graph.add_node_edge(CAMERA_DRIVER, GPU_PICK);
+}
+
+pub(crate) fn finish(app: &mut App) {
+    if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app.init_resource::<GpuPickPipeline>();
+    }
+}
+
+/// The hoverable `words` name, see `PickUniform::id`
+/// None for nothing drawn under the cursor, or an entity that's no longer hoverable
+pub(crate) fn decode(words: [u32; 2], hoverables: &Hoverables) -> ReadbackHit {
+    let [index, generation] = words;
+    let entity = index
+        .checked_sub(1)
+        .map(|index| Entity::from_bits(u64::from(generation) << 32 | u64::from(index)))
+        .filter(|entity| hoverables.contains(*entity));
+    ReadbackHit {
+        entity,
+        point: None,
+    }
+}
+
+struct ExtractedPickMesh {
+    entity: Entity,
+    mesh: AssetId<Mesh>,
+    world_from_local: Mat4,
+    cull_mode: Option<Face>,
+}
+
+#[derive(Resource, Default)]
+struct ExtractedPickMeshes(Vec<ExtractedPickMesh>);
+
+#[allow(clippy::type_complexity)]
+fn extract_pick_meshes(
+    mut extracted: ResMut<ExtractedPickMeshes>,
+    request: Extract<Res<ReadbackRequest>>,
+    settings: Extract<Res<PickingSettings>>,
+    ignored: Extract<Res<IgnoredEntities>>,
+    materials: Extract<Option<Res<Assets<StandardMaterial>>>>,
+    targets: Extract<
+        Query<
+            (
+                Entity,
+                &Handle<Mesh>,
+                &GlobalTransform,
+                &ViewVisibility,
+                Option<&Handle<StandardMaterial>>,
+            ),
+            With<Hoverable>,
+        >,
+    >,
+) {
+    extracted.0.clear();
+    if !request
+        .0
+        .is_some_and(|cursor| cursor.backend == PickingBackend::Gpu)
+    {
+        return;
+    }
+    for (entity, mesh, transform, visibility, material) in targets.iter() {
+        if !visibility.get() || ignored.0.contains(&entity) {
+            continue;
+        }
+        // culled like the CPU backend does, see `PickingSettings::cull_mode`
+        let cull_mode = material
+            .and_then(|material| materials.as_ref()?.get(material))
+            .map_or(settings.cull_mode, |material| material.cull_mode);
+        extracted.0.push(ExtractedPickMesh {
+            entity,
+            mesh: mesh.id(),
+            world_from_local: transform.compute_matrix(),
+            cull_mode,
+        });
+    }
+}
+
+#[derive(ShaderType, Clone)]
+struct PickUniform {
+    clip_from_local: Mat4,
+    /// entity index + 1, so 0 is nothing, and generation
+    id: UVec2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct GpuPickKey {
+    topology: PrimitiveTopology,
+    cull_mode: Option<Face>,
+}
+
+#[derive(Resource)]
+struct GpuPickPipeline {
+    layout: BindGroupLayout,
+    /// the one pixel ids are drawn into
+    id_texture: Texture,
+    id_view: TextureView,
+    depth_view: TextureView,
+}
+
+impl FromWorld for GpuPickPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("bevy_hover_gpu_pick_layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX_FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(PickUniform::min_size()),
+                },
+                count: None,
+            }],
+        });
+        let texture = |label, format, usage| {
+            render_device.create_texture(&TextureDescriptor {
+                label: Some(label),
+                size: Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+        let id_texture = texture(
+            "bevy_hover_gpu_pick_ids",
+            TextureFormat::Rg32Uint,
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        );
+        let depth_texture = texture(
+            "bevy_hover_gpu_pick_depth",
+            TextureFormat::Depth32Float,
+            TextureUsages::RENDER_ATTACHMENT,
+        );
+        Self {
+            layout,
+            id_view: id_texture.create_view(&TextureViewDescriptor::default()),
+            depth_view: depth_texture.create_view(&TextureViewDescriptor::default()),
+            id_texture,
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for GpuPickPipeline {
+    type Key = GpuPickKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let vertex_layout = layout.get_layout(&[Mesh::ATTRIBUTE_POSITION.at_shader_location(0)])?;
+        Ok(RenderPipelineDescriptor {
+            label: Some("bevy_hover_gpu_pick_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            push_constant_ranges: Vec::new(),
+            vertex: VertexState {
+                shader: GPU_PICK_SHADER,
+                shader_defs: Vec::new(),
+                entry_point: "vertex".into(),
+                buffers: vec![vertex_layout],
+            },
+            fragment: Some(FragmentState {
+                shader: GPU_PICK_SHADER,
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: TextureFormat::Rg32Uint,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState {
+                topology: key.topology,
+                cull_mode: key.cull_mode,
+                ..default()
+            },
+            // reverse z, like bevy's projections
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: CompareFunction::Greater,
+                stencil: default(),
+                bias: default(),
+            }),
+            multisample: MultisampleState::default(),
+        })
+    }
+}
+
+#[derive(Resource, Default)]
+struct GpuPickDraws {
+    cursor: Option<ReadbackCursor>,
+    uniforms: DynamicUniformBuffer<PickUniform>,
+    bind_group: Option<BindGroup>,
+    /// pipeline, mesh and uniform offset of each hoverable drawn
+    draws: Vec<(CachedRenderPipelineId, AssetId<Mesh>, u32)>,
+}
+
+/// Scales and moves clip space so only the pixel under the cursor is left in it, that pixel is
+/// then drawn over the whole 1x1 id target
+fn pixel_from_clip(cursor: ReadbackCursor) -> Mat4 {
+    let scale = cursor.viewport_size.as_vec2();
+    Mat4::from_cols(
+        Vec4::new(scale.x, 0.0, 0.0, 0.0),
+        Vec4::new(0.0, scale.y, 0.0, 0.0),
+        Vec4::Z,
+        (-cursor.ndc * scale).extend(0.0).extend(1.0),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prepare_gpu_pick(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline_cache: Res<PipelineCache>,
+    pipeline: Res<GpuPickPipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<GpuPickPipeline>>,
+    meshes: Res<RenderAssets<Mesh>>,
+    extracted: Res<ExtractedPickMeshes>,
+    request: Res<ReadbackRequest>,
+    mut draws: ResMut<GpuPickDraws>,
+) {
+    let draws = &mut *draws;
+    draws.draws.clear();
+    draws.uniforms.clear();
+    draws.bind_group = None;
+    draws.cursor = request
+        .0
+        .filter(|cursor| cursor.backend == PickingBackend::Gpu);
+    let Some(cursor) = draws.cursor else {
+        return;
+    };
+    let clip_from_world = pixel_from_clip(cursor) * cursor.clip_from_world;
+    for target in &extracted.0 {
+        // not uploaded yet
+        let Some(gpu_mesh) = meshes.get(target.mesh) else {
+            continue;
+        };
+        let key = GpuPickKey {
+            topology: gpu_mesh.primitive_topology,
+            cull_mode: target.cull_mode,
+        };
+        // no positions to draw
+        let Ok(pipeline_id) =
+            pipelines.specialize(&pipeline_cache, &pipeline, key, &gpu_mesh.layout)
+        else {
+            continue;
+        };
+        let offset = draws.uniforms.push(PickUniform {
+            clip_from_local: clip_from_world * target.world_from_local,
+            id: UVec2::new(target.entity.index() + 1, target.entity.generation()),
+        });
+        draws.draws.push((pipeline_id, target.mesh, offset));
+    }
+    draws.uniforms.write_buffer(&render_device, &render_queue);
+    if let Some(binding) = draws.uniforms.binding() {
+        draws.bind_group = Some(render_device.create_bind_group(
+            "bevy_hover_gpu_pick_bind_group",
+            &pipeline.layout,
+            &BindGroupEntries::single(binding),
+        ));
+    }
+}
+
+struct GpuPickNode;
+
+impl Node for GpuPickNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let draws = world.resource::<GpuPickDraws>();
+        let Some(cursor) = draws.cursor else {
+            return Ok(());
+        };
+        let pipeline = world.resource::<GpuPickPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let meshes = world.resource::<RenderAssets<Mesh>>();
+        // pipelines compile in the background, until they all have there's no telling what's on
+        // top, rather than reading back a wrong id the last readback stands
+        if draws
+            .draws
+            .iter()
+            .any(|(id, ..)| pipeline_cache.get_render_pipeline(*id).is_none())
+        {
+            return Ok(());
+        }
+
+        // cleared to id 0 even with nothing to draw, so the readback says there's nothing there
+        let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("bevy_hover_gpu_pick_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: &pipeline.id_view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(default()),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: &pipeline.depth_view,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(0.0),
+                    store: false,
+                }),
+                stencil_ops: None,
+            }),
+        });
+        if let Some(bind_group) = &draws.bind_group {
+            for &(pipeline_id, mesh, offset) in &draws.draws {
+                let (Some(render_pipeline), Some(gpu_mesh)) = (
+                    pipeline_cache.get_render_pipeline(pipeline_id),
+                    meshes.get(mesh),
+                ) else {
+                    continue;
+                };
+                pass.set_render_pipeline(render_pipeline);
+                pass.set_bind_group(0, bind_group, &[offset]);
+                pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+                match &gpu_mesh.buffer_info {
+                    GpuBufferInfo::Indexed {
+                        buffer,
+                        count,
+                        index_format,
+                    } => {
+                        pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                        pass.draw_indexed(0..*count, 0, 0..1);
+                    }
+                    GpuBufferInfo::NonIndexed => pass.draw(0..gpu_mesh.vertex_count, 0..1),
+                }
+            }
+        }
+        drop(pass);
+
+        let Some(readback) = world
+            .resource::<PendingReadbacks>()
+            .buffer(render_context.render_device(), cursor)
+        else {
+            return Ok(());
+        };
+        render_context.command_encoder().copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &pipeline.id_texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &readback,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+// Writes the id of each mesh drawn into the one pixel of the pick target, see `gpu_pick.rs`
+
+struct PickUniform {
+    // world and camera projection, then scaled so the cursor's pixel fills the clip space
+    clip_from_local: mat4x4<f32>,
+    // entity index + 1 (0 is nothing) and generation
+    id: vec2<u32>,
+};
+
+@group(0) @binding(0) var<uniform> pick: PickUniform;
+
+@vertex
+fn vertex(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return pick.clip_from_local * vec4<f32>(position, 1.0);
+}
+
+@fragment
+fn fragment() -> @location(0) vec4<u32> {
+    return vec4<u32>(pick.id, 0u, 0u);
+}
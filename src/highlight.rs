@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::map::MapRange;
+use crate::Hover;
+
+/// Lights a hoverable up while it's hovered: its `StandardMaterial`'s emissive ramps up to
+/// `emissive` over `fade`, then back down to what it was over `fade` once the hover ends
+/// Materials are shared by handle, so the entity is given its own copy of its material the first
+/// time it's hovered, the other entities using it aren't lit up along with it
+#[derive(Component, Debug, Clone)]
+pub struct HoverHighlight {
+    pub emissive: Color,
+    pub fade: Duration,
+}
+
+/// Where a highlight is in its fade, kept on the highlighted entity
+#[derive(Component)]
+pub(crate) struct HighlightFade {
+    /// the material's own emissive, faded back to
+    base: Color,
+    /// how lit up the entity is, 0 is `base` and 1 is `HoverHighlight::emissive`
+    level: f32,
+    /// `level` when the current fade started, and when that was
+    from: f32,
+    since: Duration,
+    hovered: bool,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_highlights(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(
+        Entity,
+        &HoverHighlight,
+        &mut Handle<StandardMaterial>,
+        Has<Hover>,
+        Option<&mut HighlightFade>,
+    )>,
+) {
+    let now = time.elapsed();
+    for (entity, highlight, mut handle, hovered, fade) in query.iter_mut() {
+        let Some(mut fade) = fade else {
+            if !hovered {
+                continue;
+            }
+            // meshes that haven't loaded their material yet are retried next frame
+            let Some(material) = materials.get(&*handle).cloned() else {
+                continue;
+            };
+            let base = material.emissive;
+            *handle = materials.add(material);
+            commands.entity(entity).insert(HighlightFade {
+                base,
+                level: 0.0,
+                from: 0.0,
+                since: now,
+                hovered,
+            });
+            continue;
+        };
+
+        if fade.hovered != hovered {
+            // turn around from wherever the last fade got to
+            fade.from = fade.level;
+            fade.since = now;
+            fade.hovered = hovered;
+        }
+        let target = if hovered { 1.0 } else { 0.0 };
+        let level = if highlight.fade.is_zero() {
+            target
+        } else {
+            (now - fade.since)
+                .as_secs_f32()
+                .map_clamped((0.0, highlight.fade.as_secs_f32()), (fade.from, target))
+        };
+        if level == fade.level {
+            // leaves the material alone, so it's only marked changed while fading
+            continue;
+        }
+        fade.level = level;
+        if let Some(material) = materials.get_mut(&*handle) {
+            let base = Vec4::from(fade.base.as_linear_rgba_f32());
+            let lit = Vec4::from(highlight.emissive.as_linear_rgba_f32());
+            let [r, g, b, a] = base.lerp(lit, level).to_array();
+            material.emissive = Color::rgba_linear(r, g, b, a);
+        }
+    }
+}
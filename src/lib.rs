@@ -1,18 +1,404 @@
 use bevy::prelude::*;
 
-use bevy::render::mesh::VertexAttributeValues;
+mod bounds;
+mod colliders;
+mod focus;
+mod highlight;
+pub mod map;
+mod outline;
+mod texel;
+
+pub use colliders::{HoverCapsule, HoverConvexHull, HoverHeightfield, HoverObb, HoverPlane};
+pub use focus::{FocusChanged, Focusable, Focused};
+pub use highlight::HoverHighlight;
+pub use outline::HoverOutline;
+
+/// The commonly used parts of the crate, for a single `use bevy_hover::prelude::*`
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_hover::prelude::*;
+///
+/// fn setup(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+///     commands.spawn((Camera3dBundle::default(), RaySource::Mouse));
+///     commands.spawn((
+///         PbrBundle {
+///             mesh: meshes.add(Mesh::from(shape::Cube::new(1.0))),
+///             ..default()
+///         },
+///         Hoverable,
+///     ));
+/// }
+///
+/// fn log_hovers(
+///     mut starts: EventReader<HoverStart>,
+///     mut ends: EventReader<HoverEnd>,
+///     mut clicks: EventReader<HoverClick>,
+/// ) {
+///     for start in starts.read() {
+///         info!("hovering {:?} at {}", start.hovered, start.hit.point);
+///     }
+///     for end in ends.read() {
+///         info!("left {:?}", end.hovered);
+///     }
+///     for click in clicks.read() {
+///         info!("clicked {:?}", click.entity);
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins((DefaultPlugins, MouseRayPlugin))
+///     .add_systems(Startup, setup)
+///     .add_systems(Update, log_hovers)
+///     .run();
+/// ```
+pub mod prelude {
+    pub use crate::map::MapRange;
+    pub use crate::{
+        cast_ray, cast_ray_filtered, pick_under_cursor, CurrentHover, Drag, DragEnd, DragStart,
+        FocusChanged, Focusable, Focused, Hover, HoverAppExt, HoverChanged, HoverClick, HoverEnd,
+        HoverHighlight, HoverLayer, HoverMode, HoverMove, HoverOutline, HoverPress,
+        HoverPressCancel, HoverRelease, HoverStart, Hoverable, Hovered, IgnoredEntities,
+        MouseRayPlugin, Occluder, PickMode, PickSchedule, PickingBackend, PickingSettings,
+        PickingSystems, RayHit, RaySource, VirtualCursor,
+    };
+}
+
+#[cfg(feature = "channel")]
+mod channel;
+#[cfg(feature = "channel")]
+pub use channel::HoverChangedChannel;
+
+#[cfg(feature = "depth_picking")]
+mod depth_pick;
+#[cfg(feature = "gpu_picking")]
+mod gpu_pick;
+#[cfg(any(feature = "gpu_picking", feature = "depth_picking"))]
+mod readback;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod async_raycast;
+#[cfg(not(target_arch = "wasm32"))]
+pub use async_raycast::{AsyncRaycast, AsyncRaycastResult};
+
+use bevy::core::FrameCount;
+use bevy::ecs::event::ManualEventReader;
+use bevy::ecs::query::{QuerySingleError, ReadOnlyWorldQuery, WorldQuery};
+use bevy::ecs::schedule::SystemConfigs;
+use bevy::input::InputSystem;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+use bevy::render::render_resource::{Face, PrimitiveTopology, VertexFormat};
+use bevy::render::view::VisibilitySystems;
+use bevy::transform::TransformSystem;
+use bevy::utils::{HashMap, HashSet, Instant};
+use bevy::window::WindowFocused;
+use bounds::{CpuMesh, MeshCache, ModelBounds};
+use colliders::LocalCollider;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Once};
+use std::time::Duration;
+use texel::TexelSampler;
 
 #[derive(Component, Default)]
 /// This crate only looks at `Hoverable` entities
 /// Any entities that:
 /// - have  GlobalTransform`, `Mesh` and `Hoverable` components, and
 /// - are positioned under the cursor from the camera's perspective
+///
 /// will:
 /// - be marked with the `Hovered` component, and
 /// - have their Entity id stored in `Hovered`, and
 /// - have a HoverStart event sent with their Entity id.
 pub struct Hoverable;
 
+/// Non-hoverable geometry that blocks the pick ray, like a wall in front of a hoverable
+/// Occluders are picked by the same components as hoverables (mesh, `HoverLod`,
+/// `HoverIntersect`), but never hovered themselves: if one is nearer than the nearest hoverable,
+/// nothing is hovered
+#[derive(Component, Default)]
+pub struct Occluder;
+
+/// Entities skipped by picking, both the hover pass and `cast_ray`, as if they weren't
+/// `Hoverable` (or `Occluder`) at all
+/// For temporary exclusions, like the object being dragged, which shouldn't pick itself
+/// For permanent ones, remove `Hoverable` instead
+#[derive(Resource, Debug, Default, Clone)]
+pub struct IgnoredEntities(pub HashSet<Entity>);
+
+/// Picking layer, for hoverables that should win over others no matter how far away they are,
+/// like UI-like elements drawn on top of the world
+/// If hits are on several layers, only the highest layer's are considered; depth (and
+/// `PickMode`) only decides between hits on the same layer. Occluders on a higher layer block
+/// lower layers regardless of depth, occluders on a lower layer never block higher ones.
+/// Entities without a `HoverLayer` are on layer 0
+/// `PickMode::FirstHit` stops at the first hit and ignores layers
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HoverLayer(pub u32);
+
+/// Mesh used for picking instead of the entity's own `Handle<Mesh>`
+/// For models with levels of detail: point this at a fixed (usually coarse) mesh, so hover
+/// behaves the same no matter which visual LOD is currently swapped in
+#[derive(Component, Debug, Clone)]
+pub struct HoverLod(pub Handle<Mesh>);
+
+/// Loads a dedicated collision mesh from `path` with the `AssetServer` and picks the entity by
+/// it (through a `HoverLod`), e.g. the simplified collision glb a game ships next to the visual
+/// one: `"props/crate_collision.glb#Mesh0/Primitive0"`
+/// The entity can't be hovered until the collision mesh has loaded, its visual mesh isn't used
+/// in the meantime. Changing `path` loads the new one
+#[derive(Component, Debug, Clone)]
+pub struct HoverColliderSource {
+    pub path: String,
+}
+
+/// Custom intersection logic, for shapes that aren't (or shouldn't be tested as) triangle
+/// meshes, e.g. signed distance fields or analytic surfaces
+/// Attach it to a `Hoverable` with `HoverIntersect`
+pub trait HoverIntersector: Send + Sync + 'static {
+    /// The nearest hit along `ray` (in world space), None for a miss
+    /// `transform` is the hoverable entity's. The hit's `entity` is ignored (the hoverable's is
+    /// used, `Entity::PLACEHOLDER` will do) and its `point` is taken at `distance` along `ray`;
+    /// fill in whichever of the rest the surface has
+    fn intersect(&self, ray: &Ray, transform: &GlobalTransform) -> Option<RayHit>;
+}
+
+/// Picks a `Hoverable` with a `HoverIntersector` instead of its mesh
+/// The entity doesn't need a mesh at all
+#[derive(Component, Clone)]
+pub struct HoverIntersect(pub Arc<dyn HoverIntersector>);
+
+/// Everything that makes a `Hoverable` pickable
+#[derive(WorldQuery)]
+pub(crate) struct PickTarget {
+    entity: Entity,
+    transform: Ref<'static, GlobalTransform>,
+    local: Option<Ref<'static, Transform>>,
+    parent: Option<&'static Parent>,
+    mesh: Option<&'static Handle<Mesh>>,
+    lod: Option<&'static HoverLod>,
+    intersector: Option<&'static HoverIntersect>,
+    obb: Option<&'static HoverObb>,
+    capsule: Option<&'static HoverCapsule>,
+    hull: Option<&'static HoverConvexHull>,
+    plane: Option<&'static HoverPlane>,
+    heightfield: Option<&'static HoverHeightfield>,
+    layer: Option<&'static HoverLayer>,
+    material: Option<&'static Handle<StandardMaterial>>,
+}
+
+/// Looks up an entity's `GlobalTransform`, for the parents of children that haven't been
+/// propagated yet, see `PickTargetItem::global_transform`
+pub(crate) type ParentTransforms<'a> = &'a dyn Fn(Entity) -> Option<GlobalTransform>;
+
+impl PickTargetItem<'_> {
+    /// See `HoverLayer`
+    pub(crate) fn layer(&self) -> HoverLayer {
+        self.layer.copied().unwrap_or_default()
+    }
+
+    /// The mesh picking tests against, see `HoverLod`
+    pub(crate) fn mesh_handle(&self) -> Option<&Handle<Mesh>> {
+        self.lod.map(|lod| &lod.0).or(self.mesh)
+    }
+
+    /// Where the entity is picked, its `GlobalTransform`
+    /// Entities spawned (or moved) since transform propagation last ran, e.g. earlier this frame
+    /// for `cast_ray` from `Update`, have a `Transform` that changed no earlier than their
+    /// `GlobalTransform`. Those without a parent are picked at their `Transform` instead, which is
+    /// where propagation puts them. A child is picked at its parent's `GlobalTransform` composed
+    /// with its `Transform`, or is None if the parent has none
+    pub(crate) fn global_transform(&self, parents: ParentTransforms) -> Option<GlobalTransform> {
+        let Some(local) = &self.local else {
+            return Some(*self.transform);
+        };
+        // ticks wrap around, compared by their difference
+        // spawning inserts both in the same tick, propagation writes the `GlobalTransform` after
+        let since_propagated = local
+            .last_changed()
+            .get()
+            .wrapping_sub(self.transform.last_changed().get());
+        let stale = (since_propagated as i32) >= 0;
+        match (stale, self.parent) {
+            (false, _) => Some(*self.transform),
+            (true, None) => Some(GlobalTransform::from(**local)),
+            (true, Some(parent)) => Some(parents(parent.get())?.mul_transform(**local)),
+        }
+    }
+
+    /// `bounds` is optional, without it meshes are tested triangle by triangle every time
+    /// so are `materials`, without them faces are culled by `PickingSettings::cull_mode`
+    pub(crate) fn shape<'a>(
+        &'a self,
+        mesh_assets: &'a Assets<Mesh>,
+        bounds: Option<&'a MeshCache>,
+        materials: Option<&Assets<StandardMaterial>>,
+        parents: ParentTransforms,
+    ) -> PickShape<'a> {
+        let Some(transform) = self.global_transform(parents) else {
+            // nothing to hit, see `global_transform`
+            return PickShape {
+                transform: GlobalTransform::IDENTITY,
+                mesh: None,
+                bounds: None,
+                cpu_mesh: None,
+                intersector: None,
+                collider: None,
+                cull_mode: None,
+            };
+        };
+        PickShape {
+            transform,
+            mesh: self
+                .mesh_handle()
+                .and_then(|handle| mesh_assets.get(handle)),
+            bounds: self
+                .mesh_handle()
+                .and_then(|handle| bounds?.get(handle.id())),
+            cpu_mesh: self
+                .mesh_handle()
+                .and_then(|handle| bounds?.cpu_mesh(handle.id())),
+            intersector: self.intersector.map(|intersect| &*intersect.0),
+            collider: self.collider(),
+            cull_mode: self
+                .material
+                .and_then(|handle| materials?.get(handle))
+                .map(|material| material.cull_mode),
+        }
+    }
+
+    /// The entity's analytic collider, if it has one
+    pub(crate) fn collider(&self) -> Option<&dyn LocalCollider> {
+        self.obb
+            .map(|obb| obb as &dyn LocalCollider)
+            .or(self.capsule.map(|capsule| capsule as &dyn LocalCollider))
+            .or(self.hull.map(|hull| hull as &dyn LocalCollider))
+            .or(self.plane.map(|plane| plane as &dyn LocalCollider))
+            .or(self
+                .heightfield
+                .map(|heightfield| heightfield as &dyn LocalCollider))
+    }
+}
+
+/// The shapes an entity is picked by, borrowed from wherever they're stored
+pub(crate) struct PickShape<'a> {
+    pub(crate) transform: GlobalTransform,
+    pub(crate) mesh: Option<&'a Mesh>,
+    /// `mesh`'s bounds, to skip its triangles when the ray misses them
+    pub(crate) bounds: Option<ModelBounds>,
+    /// `mesh`'s triangles, tested instead of reading them from `mesh` when there are any
+    pub(crate) cpu_mesh: Option<&'a CpuMesh>,
+    pub(crate) intersector: Option<&'a dyn HoverIntersector>,
+    pub(crate) collider: Option<&'a dyn LocalCollider>,
+    /// faces the entity's material culls, None to fall back to `PickingSettings::cull_mode`
+    pub(crate) cull_mode: Option<Option<Face>>,
+}
+
+impl PickShape<'_> {
+    /// Lower bound on the distance along `ray` of any hit, from the mesh's cached bounds
+    /// 0 when there's nothing to bound the shape with, None if the ray can't hit it at all
+    pub(crate) fn min_distance(&self, ray: &Ray) -> Option<f32> {
+        let (None, None, Some(mesh), Some(bounds)) =
+            (self.intersector, self.collider, self.mesh, self.bounds)
+        else {
+            return Some(0.0);
+        };
+        if let PrimitiveTopology::LineList | PrimitiveTopology::LineStrip =
+            mesh.primitive_topology()
+        {
+            // lines are hit from a distance, see `PickingSettings::line_pick_pixels`
+            return Some(0.0);
+        }
+        let local_ray = LocalRay::new(ray, &self.transform)?;
+        let t = bounds.entry(local_ray.origin, local_ray.direction)?;
+        Some(local_ray.world_distance(t, ray))
+    }
+
+    /// Whether `ray` passes through the mesh's cached bounds, without testing its triangles
+    /// Custom intersectors and analytic colliders have no bounds, and are tested exactly instead
+    pub(crate) fn hits_bounds(&self, ray: &Ray, settings: &PickingSettings) -> bool {
+        if self.intersector.is_some() || self.collider.is_some() {
+            return self
+                .intersect(ray, settings, ScreenTolerance::default())
+                .is_some();
+        }
+        // meshes that haven't loaded yet have no bounds
+        let Some(bounds) = self.bounds else {
+            return false;
+        };
+        LocalRay::new(ray, &self.transform)
+            .is_some_and(|local_ray| bounds.intersects(local_ray.origin, local_ray.direction))
+    }
+
+    /// How many triangles `intersect` tests, 0 for shapes that aren't picked by their triangles
+    pub(crate) fn triangle_count(&self) -> usize {
+        let (None, None, Some(mesh)) = (self.intersector, self.collider, self.mesh) else {
+            return 0;
+        };
+        let vertices = mesh
+            .indices()
+            .map_or(mesh.count_vertices(), |indices| indices.len());
+        match mesh.primitive_topology() {
+            PrimitiveTopology::TriangleList => vertices / 3,
+            PrimitiveTopology::TriangleStrip => vertices.saturating_sub(2),
+            _ => 0,
+        }
+    }
+
+    /// `tolerance` holds the settings measured in pixels, see `ScreenTolerance`
+    pub(crate) fn intersect(
+        &self,
+        ray: &Ray,
+        settings: &PickingSettings,
+        tolerance: ScreenTolerance,
+    ) -> Option<MeshHit> {
+        // a custom intersector replaces the mesh
+        if let Some(intersector) = self.intersector {
+            return intersector
+                .intersect(ray, &self.transform)
+                .map(MeshHit::from);
+        }
+        // so does an analytic collider
+        if let Some(collider) = self.collider {
+            let local_ray = LocalRay::new(ray, &self.transform)?;
+            let t = collider
+                .intersect_local(local_ray.origin, local_ray.direction)
+                .filter(|t| *t > settings.near_epsilon)?;
+            return Some(MeshHit::at(local_ray.world_distance(t, ray)));
+        }
+        let mesh = self.mesh?;
+        match mesh.primitive_topology() {
+            PrimitiveTopology::LineList | PrimitiveTopology::LineStrip => {
+                check_line_intersect(ray, mesh, &self.transform, tolerance.line?, settings)
+            }
+            _ => {
+                let cull_mode = self.cull_mode.unwrap_or(settings.cull_mode);
+                check_intersect(
+                    ray,
+                    mesh,
+                    self.cpu_mesh,
+                    self.bounds,
+                    &self.transform,
+                    cull_mode,
+                    tolerance.min_triangle,
+                    settings,
+                )
+                .or_else(|| {
+                    check_edge_intersect(
+                        ray,
+                        mesh,
+                        self.cpu_mesh,
+                        &self.transform,
+                        tolerance.radius?,
+                        settings,
+                    )
+                })
+            }
+        }
+    }
+}
+
 #[derive(Component)]
 /// Hover component marks the Hoverable entity the mouse is currently over
 /// At any time, at most 1 entity is Hover
@@ -20,21 +406,425 @@ pub struct Hoverable;
 pub struct Hover {
     /// time elapsed from app start to hover event start
     pub since: std::time::Duration,
+    /// where the ray hit the entity, as of the last frame it was tested
+    pub hit: RayHit,
+}
+
+/// Where a ray hit a pickable entity
+/// Returned by `cast_ray` and `AsyncRaycast`, and carried by `Hover` and `HoverStart`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    /// world space distance from the ray origin
+    pub distance: f32,
+    /// world space
+    pub point: Vec3,
+    /// world space normal of the hit triangle, on its front (counter-clockwise) side
+    /// None for hits that aren't on a triangle, see `triangle`
+    pub normal: Option<Vec3>,
+    /// texture coordinates at `point`, None if the mesh has no `ATTRIBUTE_UV_0`
+    pub uv: Option<Vec2>,
+    /// vertex indices of the hit triangle
+    /// None for hits on lines, analytic colliders, and `HoverIntersector`s that don't set it
+    pub triangle: Option<[usize; 3]>,
+    /// barycentric coordinates of `point` in `triangle`, weights of its 3 vertices in order
+    pub barycentric: Option<Vec3>,
+    /// world space positions of `triangle`'s vertices, in the same order, e.g. to outline the
+    /// picked face or snap to its corners
+    pub vertices: Option<[Vec3; 3]>,
 }
 
 #[derive(Resource)]
 pub struct Hovered {
     pub inner: Option<Entity>,
+    /// entities hovered along with `inner`, see `PickingSettings::hover_band`
+    /// always empty without it
+    pub others: Vec<Entity>,
+}
+
+/// Default for `PickingSettings::epsilon`
+pub const DEFAULT_EPSILON: f32 = 0.000_001;
+
+/// Tunables for the picking pass
+/// Insert your own before adding `MouseRayPlugin` to override the defaults
+#[derive(Resource, Debug, Clone)]
+pub struct PickingSettings {
+    /// tolerance of the ray-triangle test: rays this close to parallel with a triangle, and
+    /// triangles this close to zero area, count as misses
+    /// scale it with your scene's units, e.g. much smaller if 1.0 is a kilometer
+    pub epsilon: f32,
+    /// hits closer than this to the ray origin are discarded
+    /// measured along the (normalized) ray, so it's in world units
+    ///
+    /// perspective rays start at the camera's focal point, so this only matters for objects
+    /// touching the camera
+    /// orthographic rays start on the camera's near plane (see `screen_ndc_to_ray`), so this
+    /// narrows it: faces closer to the near plane than `near_epsilon` can't be picked, lower it
+    /// (or set it to 0) if objects sit right at the plane
+    pub near_epsilon: f32,
+    /// how far behind the near plane orthographic rays start, in world units
+    ///
+    /// the near plane bounds what the camera renders, so this is only needed to pick objects
+    /// that aren't rendered, in front of it
+    pub ortho_near_offset: f32,
+    /// treat orthographic rays as a full line through the near plane, so objects on the
+    /// camera's side of it are picked too, still frontmost first
+    /// hits on that side get negative distances: `RayHit::distance` of the hover pass is then
+    /// signed, measured from the near plane. Replaces `ortho_near_offset`
+    pub ortho_full_line: bool,
+    /// test at most this many hoverables per frame, None to test all of them every frame
+    ///
+    /// for very large scenes: the hoverables are swept round-robin, a few each frame, and the
+    /// hover state only updates once every hoverable has been tested. In between, the result of
+    /// the last complete sweep stays in place.
+    /// The tradeoff is latency and accuracy: a sweep over N hoverables takes
+    /// `ceil(N / max_entities_per_frame)` frames to report a change, and since the cursor and the
+    /// objects keep moving during a sweep, hits from different frames get compared with each other.
+    pub max_entities_per_frame: Option<usize>,
+    /// ignore hits in front of a perspective camera's near plane, or behind its far plane,
+    /// so only what's actually rendered can be picked
+    /// no effect on orthographic cameras
+    pub clamp_to_clip_planes: bool,
+    /// read the hovered texel's color into `HoverStart::texel`, e.g. for eyedropper tools
+    /// costs an extra material and image lookup per `HoverStart`
+    pub sample_texels: bool,
+    /// when `HoverStart` is sent, see `HoverMode`
+    pub mode: HoverMode,
+    /// which hoverable wins when the ray hits several, see `PickMode`
+    pub pick_mode: PickMode,
+    /// how to read `CursorMoved` positions, for custom input sources (drawing tablets, remote
+    /// control) that send their own events with a different origin or y direction
+    pub cursor_convention: CursorConvention,
+    /// pick `LineList`/`LineStrip` meshes (wireframes, gizmo axes) when the ray passes within
+    /// this many logical pixels of one of their edges, None to leave them unpickable
+    /// only the hover pass can pick lines, `cast_ray` and `AsyncRaycast` have no camera to
+    /// measure pixels with
+    pub line_pick_pixels: Option<f32>,
+    /// keep the hovered entity while the ray is still on it, unless another one is hit at least
+    /// `sticky_margin` nearer (further with `PickMode::Farthest`)
+    /// reduces flicker between overlapping or interleaved meshes, and makes small targets
+    /// easier to stay on
+    pub sticky: bool,
+    /// how much nearer another hit has to be to take the hover from the current entity, in world
+    /// units, only used with `sticky`
+    pub sticky_margin: f32,
+    /// hover every entity hit within this distance (in world units) of the picked one, not just
+    /// the picked one, e.g. for coincident faces where the user means "all of these"
+    /// each gets its own `Hover`, `HoverStart` and `HoverEnd`; the picked one stays in
+    /// `Hovered::inner`, the rest go to `Hovered::others`
+    /// None to only ever hover one entity
+    pub hover_band: Option<f32>,
+    /// plane the cursor is tracked across during a drag, see `Drag::delta`
+    pub drag_plane: DragPlane,
+    /// send a `HoverChanged` on the first frame, even if nothing is hovered
+    pub initial_hover_changed: bool,
+    /// faces that can't be picked when the entity has no `StandardMaterial` to say, e.g.
+    /// `Some(Face::Back)` to only hit the sides meshes are usually seen from
+    /// a `StandardMaterial`'s own `cull_mode` takes precedence, so picking matches rendering:
+    /// double sided materials are hit from both sides, back culled ones only from the front
+    pub cull_mode: Option<Face>,
+    /// keys that press the `Focused` entity, or the hovered one if nothing is focused, like the
+    /// left mouse button would: a `HoverPress` when one goes down, then a `HoverRelease` and a
+    /// `HoverClick` once they're all up again. Empty to turn keyboard activation off
+    pub activation_keys: Vec<KeyCode>,
+    /// don't send another `HoverStart` for an entity within this long of its last one, e.g. so
+    /// quick pass-overs don't spam the sounds or particles started on hover. None to send them all
+    /// only the event is held back: the entity still gets its `Hover` and is `Hovered`, and its
+    /// `HoverEnd` is still sent
+    pub hover_cooldown: Option<Duration>,
+    /// also hover triangle meshes the ray passes within this many logical pixels of, e.g. to
+    /// make thin or distant objects easier to point at. None for exact hits only
+    /// the radius is measured on screen, so in world units it grows with distance under a
+    /// perspective camera: a far object needs the cursor as close to it on screen as a near one
+    /// near misses are measured to the mesh's triangle edges and have no `RayHit::triangle`.
+    /// They test every edge of every hoverable the ray misses, and turn off the bounds
+    /// early-out, so this is much slower on big scenes. Only the hover pass uses it, like
+    /// `line_pick_pixels`
+    pub pick_radius_pixels: Option<f32>,
+    /// skip triangles that cover less than this many square logical pixels on screen, e.g. so
+    /// the far, dense parts of a mesh don't flicker between slivers. None to hit any triangle
+    /// a skipped triangle's neighbours (or what's behind it) can still be hit. Only the hover pass
+    /// uses it, like `line_pick_pixels`
+    pub min_triangle_pixels: Option<f32>,
+    /// how close in distance (in world units) hits have to be for `PickMode::ScreenClosest` to
+    /// pick between them by where they are on screen
+    pub screen_closest_margin: f32,
+    /// how the hover pass finds what's under the cursor, see `PickingBackend`
+    pub backend: PickingBackend,
 }
 
+/// World space plane `Drag::delta` is measured on
+/// The default is the z = 0 plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragPlane {
+    /// any point on the plane
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+impl Default for DragPlane {
+    fn default() -> Self {
+        Self {
+            point: Vec3::ZERO,
+            normal: Vec3::Z,
+        }
+    }
+}
+
+impl DragPlane {
+    /// Where `ray` crosses the plane, None if it's parallel to it or points away
+    fn intersect(&self, ray: Ray) -> Option<Vec3> {
+        let t = pick_plane(ray.origin, ray.direction, self.point, self.normal)?;
+        Some(ray.get_point(t))
+    }
+}
+
+/// Which hoverable is hovered when the ray hits several
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PickMode {
+    /// the one closest to the camera
+    #[default]
+    Nearest,
+    /// whichever is found first, not necessarily the nearest
+    /// cheapest: testing stops at the first hit. Good enough when hoverables never overlap on
+    /// screen
+    FirstHit,
+    /// the one furthest from the camera, e.g. to pick the back wall of a box from outside
+    Farthest,
+    /// the nearest one, except among hits within `PickingSettings::screen_closest_margin` of each
+    /// other: of those, the one whose hit triangle's center is closest to the cursor on screen
+    /// feels more natural where faces meet at nearly the same depth, like the seams between the
+    /// segments of a sphere. Hits that aren't on a triangle count as right under the cursor
+    ScreenClosest,
+}
+
+/// How the hover pass finds the entity under the cursor
+/// The GPU backends need the renderer (`DefaultPlugins`, added before `MouseRayPlugin`) and read
+/// what's under the cursor back a frame or two late. Until something is read back, and while
+/// `PickRayOverride` is set, they fall back to `Cpu`. Only the hover pass uses them: `cast_ray`,
+/// `pick_under_cursor` and `AsyncRaycast` always test on the CPU
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PickingBackend {
+    /// ray tests against the triangles (or collider) of every hoverable
+    #[default]
+    Cpu,
+    /// renders the ids of visible hoverables into the pixel under the cursor and reads the one
+    /// on top back, so only that entity's triangles are tested, however many hoverables there
+    /// are. Picks the rendered `Handle<Mesh>`, not a `HoverLod`, and ignores colliders and
+    /// intersectors. Needs the `gpu_picking` feature
+    #[cfg(feature = "gpu_picking")]
+    Gpu,
+    /// reads the camera's prepass depth under the cursor back and hovers the hoverable whose
+    /// bounds the surface there is in, without testing any triangles. Exact to what's rendered,
+    /// occluders included, but only sees meshes drawn in the prepass. The `RaySource::Mouse`
+    /// camera needs a `DepthPrepass`, without one picking stays on the CPU. Needs the
+    /// `depth_picking` feature
+    #[cfg(feature = "depth_picking")]
+    Depth,
+}
+
+impl PickMode {
+    /// true if hit `a` should replace the current pick `b`
+    fn prefers(self, a: (f32, Entity), b: (f32, Entity)) -> bool {
+        match self {
+            PickMode::Nearest => hit_order(a, b).is_lt(),
+            PickMode::FirstHit => false,
+            PickMode::Farthest => hit_order(a, b).is_gt(),
+            // outside the margin, see `update_hover_state` for within it
+            PickMode::ScreenClosest => hit_order(a, b).is_lt(),
+        }
+    }
+}
+
+/// Coordinate system of incoming cursor positions, in logical pixels
+/// The default matches winit: origin at the top left of the window, y pointing down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CursorConvention {
+    pub origin: CursorOrigin,
+    /// y grows upwards instead of downwards
+    pub y_up: bool,
+}
+
+/// Where (0, 0) is, see `CursorConvention`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CursorOrigin {
+    /// the window's top left corner
+    #[default]
+    TopLeft,
+    /// the window's center
+    Center,
+}
+
+/// When `HoverStart` events are sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HoverMode {
+    /// once, when the cursor moves onto an entity
+    #[default]
+    Edge,
+    /// every frame the cursor is over an entity
+    /// for systems that can't keep track of what's hovered between frames
+    /// `HoverEnd` is still only sent once, when the cursor leaves
+    Polling,
+}
+
+impl Default for PickingSettings {
+    fn default() -> Self {
+        Self {
+            epsilon: DEFAULT_EPSILON,
+            near_epsilon: 0.000_001,
+            ortho_near_offset: 0.0,
+            ortho_full_line: false,
+            max_entities_per_frame: None,
+            clamp_to_clip_planes: false,
+            sample_texels: false,
+            mode: HoverMode::Edge,
+            pick_mode: PickMode::Nearest,
+            cursor_convention: CursorConvention::default(),
+            line_pick_pixels: None,
+            sticky: false,
+            sticky_margin: 0.1,
+            hover_band: None,
+            drag_plane: DragPlane::default(),
+            initial_hover_changed: false,
+            activation_keys: vec![KeyCode::Return, KeyCode::Space],
+            cull_mode: None,
+            hover_cooldown: None,
+            pick_radius_pixels: None,
+            min_triangle_pixels: None,
+            screen_closest_margin: 0.01,
+            backend: PickingBackend::Cpu,
+        }
+    }
+}
+
+/// Cursor position set from code instead of the mouse, for tests, replays and automation
+/// While this is `Some`, `CursorMoved` events are ignored and the pick ray goes through this
+/// position instead. It doesn't need a `Window` at all, so picking works headless.
+/// The position is in normalized device coordinates, like `MouseRay::cursor_to_pos` returns:
+/// (-1,-1) is the bottom left of the camera's view and (1,1) is the upper right
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct VirtualCursor(pub Option<Vec2>);
+
+/// The ray picking is done with this frame, in world space
+/// Written by the plugin whenever the mouse ray (or `PickRayOverride`) changes, for systems that
+/// want to cast their own queries along it
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct PickRay {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl From<Ray> for PickRay {
+    fn from(ray: Ray) -> Self {
+        Self {
+            origin: ray.origin,
+            direction: ray.direction,
+        }
+    }
+}
+
+impl From<PickRay> for Ray {
+    fn from(ray: PickRay) -> Self {
+        Self {
+            origin: ray.origin,
+            direction: ray.direction,
+        }
+    }
+}
+
+/// What the last hover pass cost, for finding out where picking time goes without a profiler
+/// Reset every frame the hover pass runs
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct PickingStats {
+    /// hoverables tested against the ray past their bounds
+    pub entities_tested: usize,
+    /// triangles tested in those, analytic colliders and custom intersectors count none
+    pub triangles_tested: usize,
+    /// hoverables skipped by their bounds alone, either missed by the ray or behind the
+    /// nearest hit
+    pub broad_phase_rejected: usize,
+    /// wall time of the whole pass, in microseconds
+    pub time_us: u64,
+    /// meshes whose bounds were built this frame, 0 once every picked mesh is cached; meshes
+    /// shared by many hoverables are only built once
+    pub meshes_built: usize,
+}
+
+/// Whether the mouse ray passes through the bounds of any `Hoverable` this frame
+/// Much cheaper than the full hover pass, which still tests triangles inside the bounds, so it can
+/// be true while nothing is hovered. Enough to e.g. decide whether to show a hover cursor icon
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CursorOverBounds(pub bool);
+
+/// Pick ray set from code, e.g. to drive picking along a scripted path or from a VR controller
+/// While this is `Some`, the cursor and the `RaySource::Mouse` camera are ignored (there doesn't
+/// need to be one) and hover state is computed along this ray instead
+/// `direction` should be normalized, hit distances are measured in multiples of it
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct PickRayOverride(pub Option<PickRay>);
+
+/// What a `RaySource` hovers: a mirror of `Hovered` on the `RaySource::Mouse` camera, the nearest
+/// hit along the ray on `RaySource::Forward` ones
+/// Only changes when the hovered entity does, so reactive systems can filter on
+/// `Changed<CurrentHover>` instead of reading events
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrentHover(pub Option<Entity>);
+
+/// Sent when the cursor moves onto a hoverable (every frame with `HoverMode::Polling`)
+/// When the hover moves straight from one entity to another, the old entity's `HoverEnd` is
+/// sent in the same frame, always before this, so at most one entity is ever hovered
 #[derive(Event, Debug)]
 pub struct HoverStart {
     pub hovered: Entity,
+    /// the pick ray that hit `hovered`, in world space
+    pub ray: Ray,
+    /// where `ray` hit `hovered`
+    pub hit: RayHit,
+    /// color of `hovered`'s `StandardMaterial::base_color_texture` where the ray hit it
+    /// only sampled with `PickingSettings::sample_texels`, None if the mesh has no uvs, the
+    /// material has no texture, or the texture's format can't be read on the CPU
+    pub texel: Option<Color>,
+    /// `FrameCount` of the frame this was sent in, to line events up with frames when
+    /// recording, replaying or sending them over the network
+    pub frame: u32,
 }
 
+/// Sent whenever `Hovered::inner` changes, after its `HoverEnd` and `HoverStart`
+/// For mirroring hover state (e.g. into UI) without pairing up start and end events
+/// With `PickingSettings::initial_hover_changed`, also sent once on the first frame even if
+/// nothing is hovered, so mirrors get a baseline
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoverChanged {
+    pub from: Option<Entity>,
+    pub to: Option<Entity>,
+    /// see `HoverStart::frame`
+    pub frame: u32,
+}
+
+/// Sent when the cursor leaves a hoverable, see `HoverStart` for the ordering between the two
+/// Since they are separate event types, a system reacting to both should read `HoverEnd`s
+/// first (e.g. release a shared resource before the next entity acquires it)
 #[derive(Event, Debug)]
 pub struct HoverEnd {
     pub hovered: Entity,
+    /// where the ray last hit `hovered` before leaving it, in world space
+    /// the same as the last `Hover::hit`'s point
+    pub last_point: Vec3,
+    /// see `HoverStart::frame`
+    pub frame: u32,
+}
+
+/// Sent when the point the ray hits a hovered entity at moves, while it stays hovered
+/// Not sent on the frame the entity is entered, that's its `HoverStart`
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct HoverMove {
+    pub hovered: Entity,
+    /// where the ray hits `hovered` now
+    pub hit: RayHit,
+    /// the mouse buttons held down as it moved, e.g. to paint across faces while dragging
+    pub buttons: Vec<MouseButton>,
+    /// see `HoverStart::frame`
+    pub frame: u32,
 }
 
 #[derive(Event, Debug)]
@@ -49,84 +839,336 @@ pub struct HoverRelease {
     pub button: MouseButton
 }
 
+/// Sent when a button is pressed and released on the same hoverable, after its `HoverRelease`
+/// Keyboard activation (see `PickingSettings::activation_keys`) clicks with `MouseButton::Left`
+#[derive(Event, Debug)]
+pub struct HoverClick {
+    pub entity: Entity,
+    pub button: MouseButton,
+}
+
+/// Sent instead of `HoverClick` when a button is pressed on a hoverable, but released after the
+/// cursor left it, e.g. to un-press a button without triggering it
+/// Also sent for every press in flight when the window loses focus
+#[derive(Event, Debug)]
+pub struct HoverPressCancel {
+    /// the entity the button was pressed on
+    pub entity: Entity,
+    pub button: MouseButton,
+}
+
+/// Entity that keeps the hover while the cursor is off it, like HTML pointer capture, e.g. for
+/// sliders and knobs that are dragged past their edge
+/// While set, the captured entity keeps its `Hover` (updated whenever the ray does hit it), no
+/// other entity is hovered, and its `HoverEnd` is held back until the capture ends
+/// Set automatically by a button press on a hoverable, which may start a drag. Set it by hand to
+/// capture any hovered entity; it's cleared once no mouse button is held anymore, or when the
+/// window loses focus. Capturing an entity that isn't hovered does nothing until it is
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PointerCapture(pub Option<Entity>);
+
+/// Entity the pick ray actually hits as of the last complete sweep, regardless of `PointerCapture`
+/// Tells a click from a cancelled press: the captured entity stays hovered with the cursor off it
+#[derive(Resource, Debug, Default)]
+pub(crate) struct RayPick(Option<Entity>);
+
+/// What a GPU `PickingBackend` last read back under the cursor
+/// None with `PickingBackend::Cpu`, and until the first readback arrives
+#[derive(Resource, Debug, Default)]
+pub(crate) struct ReadbackPick(Option<ReadbackHit>);
+
+// only the GPU backends build these
+#[cfg_attr(
+    not(any(feature = "gpu_picking", feature = "depth_picking")),
+    allow(dead_code)
+)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReadbackHit {
+    /// the hoverable rendered under the cursor, None for none
+    entity: Option<Entity>,
+    /// world space surface point under the cursor, for backends that read depth
+    /// Taken as the hit instead of testing `entity`'s triangles
+    point: Option<Vec3>,
+}
+
+/// Sent when the cursor first moves while a button is held down on a hoverable
+/// Followed by `Drag` events for every further movement, and a `DragEnd`
+#[derive(Event, Debug)]
+pub struct DragStart {
+    pub entity: Entity,
+    pub button: MouseButton,
+}
+
+/// Sent every frame the cursor moves during a drag, see `DragStart`
+#[derive(Event, Debug)]
+pub struct Drag {
+    pub entity: Entity,
+    pub button: MouseButton,
+    /// how far the cursor moved across `PickingSettings::drag_plane` since the last `Drag`, in
+    /// world units: add it to the entity's translation to drag it along 1:1 with the cursor
+    pub delta: Vec3,
+}
+
+/// Sent when the button of a drag is released, or the window loses focus mid-drag
+#[derive(Event, Debug)]
+pub struct DragEnd {
+    pub entity: Entity,
+    pub button: MouseButton,
+}
+
 #[derive(Component, Default)]
 struct MouseRay {
+    /// last known cursor position, see `cursor_to_pos`
+    cursor: Option<Vec2>,
     ray: Ray,
+    /// distances along `ray` between the camera's near and far planes
+    /// None for orthographic cameras
+    clip_range: Option<RangeInclusive<f32>>,
+    /// world space size of a pixel along `ray`, None until the camera has a viewport size
+    pixel_size: Option<PixelSize>,
+    /// how far `ray` starts behind the near plane for `PickingSettings::ortho_full_line`,
+    /// taken off hit distances so they're measured from the plane
+    line_offset: f32,
+    /// there's no camera to cast `ray` from, nothing is hovered until there is
+    detached: bool,
 }
 
-#[derive(Component)]
-pub struct MouseRaySource;
+/// World space size of one logical pixel, `at(distance)` along a camera ray
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PixelSize {
+    per_distance: f32,
+    constant: f32,
+}
+
+impl PixelSize {
+    pub(crate) fn at(&self, distance: f32) -> f32 {
+        self.per_distance * distance + self.constant
+    }
+
+    fn scale(self, pixels: f32) -> Self {
+        Self {
+            per_distance: self.per_distance * pixels,
+            constant: self.constant * pixels,
+        }
+    }
+}
+
+/// The `PickingSettings` measured in pixels, in world units along a camera ray
+/// All None for rays that aren't cast from a camera, there are no pixels to measure with then
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct ScreenTolerance {
+    /// how far from a line mesh's edges the ray still hits it, None to never hit line meshes
+    /// see `PickingSettings::line_pick_pixels`
+    pub(crate) line: Option<PixelSize>,
+    /// how far from a triangle mesh's edges a ray that misses its triangles still hits it
+    /// see `PickingSettings::pick_radius_pixels`
+    pub(crate) radius: Option<PixelSize>,
+    /// the size of one square pixel, and how many of them a hit triangle has to cover
+    /// see `PickingSettings::min_triangle_pixels`
+    pub(crate) min_triangle: Option<(PixelSize, f32)>,
+}
+
+impl ScreenTolerance {
+    pub(crate) fn new(settings: &PickingSettings, pixel_size: Option<PixelSize>) -> Self {
+        let scaled = |pixels: Option<f32>| {
+            pixels
+                .zip(pixel_size)
+                .map(|(pixels, pixel_size)| pixel_size.scale(pixels))
+        };
+        Self {
+            line: scaled(settings.line_pick_pixels),
+            radius: scaled(settings.pick_radius_pixels),
+            min_triangle: pixel_size.zip(settings.min_triangle_pixels),
+        }
+    }
+
+    /// Only what occluders are tested with, so they block exactly what's drawn
+    pub(crate) fn lines_only(self) -> Self {
+        Self {
+            line: self.line,
+            ..default()
+        }
+    }
+}
+
+/// Where a pick ray comes from
+/// Each source resolves its own hover, mirrored in a `CurrentHover` on the source entity
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaySource {
+    /// From a camera, through the cursor. Drives `Hovered`, `Hover` and the hover events
+    /// There should be exactly one, picking is skipped while there's none
+    /// The camera can be a child of another entity (e.g. a moving rig), the ray is always cast
+    /// from its `GlobalTransform`
+    Mouse,
+    /// From the entity's `GlobalTransform`, along its forward (`-Z`) axis, e.g. a turret, a laser
+    /// pointer or a gaze. Only updates the source's `CurrentHover`
+    /// For XR controllers, put it on the entity the XR plugin poses, or on a child of it for a
+    /// different aim. Each controller then hovers on its own
+    Forward,
+}
 
 /// Ray extending from the image plane, through the mouse pointer, into the scene
 impl MouseRay {
-    /// returns cursor position in window space
+    /// returns cursor position in the camera's viewport
     /// (-1,-1) -> bottom left and (1,1) -> upper right
-    pub(crate) fn cursor_to_pos(position: &Vec2, window: &Window) -> Vec2 {
-        let (window_width, window_height) = (window.width(), window.height());
+    /// `viewport` is the camera's viewport in logical pixels (see `Camera::logical_viewport_rect`),
+    /// None for the whole window. A camera inset in the window (e.g. a 3D preview pane) only sees
+    /// part of it, positions are relative to that part
+    pub(crate) fn cursor_to_pos(
+        position: &Vec2,
+        window: &Window,
+        viewport: Option<Rect>,
+        convention: CursorConvention,
+    ) -> Vec2 {
+        let window_size = Vec2::new(window.width(), window.height());
+        // cursor_pos is usually from a `winit::CursorMoved` event
+        // where positive x goes right and positive y goes **down**
+        // see https://docs.rs/winit/latest/winit/event/enum.WindowEvent.html#variant.CursorMoved
+        // other input sources may count y upwards, normalize to y down first
+        let y_down = if convention.y_up {
+            -position.y
+        } else {
+            position.y
+        };
+        // then to winit's origin, the window's top left
+        let window_pos = match convention.origin {
+            CursorOrigin::TopLeft => Vec2::new(position.x, y_down),
+            CursorOrigin::Center => Vec2::new(position.x, y_down) + window_size / 2.0,
+        };
+        let viewport = viewport.unwrap_or(Rect::from_corners(Vec2::ZERO, window_size));
+        let normalized = (window_pos - viewport.min) / viewport.size();
         Vec2::new(
-            position.x / window_width * 2.0 - 1.0,
-            // cursor_pos is from a `winit::CursorMoved` event
-            // where positive x goes right and positive y goes **down**
-            // see https://docs.rs/winit/latest/winit/event/enum.WindowEvent.html#variant.CursorMoved
+            normalized.x * 2.0 - 1.0,
             // in bevy, positive y goes **up**
             // flip y to convert
-            1.0 - (position.y / window_height * 2.0),
+            1.0 - normalized.y * 2.0,
         )
     }
 
+    fn update(
+        &mut self,
+        camera: &Camera,
+        projection: &Projection,
+        transform: &GlobalTransform,
+        cursor_pos: Vec2,
+        settings: &PickingSettings,
+    ) {
+        self.ray = Self::pos_from_camera(camera, projection, transform, cursor_pos, settings);
+        self.line_offset = match projection {
+            // nothing further back than `far` is in a sensible scene, without pushing the origin
+            // so far out that hit distances lose precision
+            Projection::Orthographic(orthographic) if settings.ortho_full_line => {
+                self.ray.origin -= self.ray.direction.normalize() * orthographic.far;
+                orthographic.far
+            }
+            _ => 0.0,
+        };
+        self.clip_range = match projection {
+            Projection::Perspective(perspective) => {
+                // near and far are depths along the camera's view direction
+                // the ray is slanted relative to it (except through the image center),
+                // so it travels further than that to reach either plane
+                let cos = self.ray.direction.dot(transform.forward().normalize());
+                (cos > 0.0).then(|| perspective.near / cos..=perspective.far / cos)
+            }
+            Projection::Orthographic(_) => None,
+        };
+        self.pixel_size = camera.logical_viewport_size().map(|size| match projection {
+            // the view frustum's height grows linearly with distance
+            // (strictly with depth, the difference is negligible for a few pixels)
+            Projection::Perspective(perspective) => PixelSize {
+                per_distance: 2.0 * (perspective.fov / 2.0).tan() / size.y,
+                constant: 0.0,
+            },
+            Projection::Orthographic(orthographic) => PixelSize {
+                per_distance: 0.0,
+                constant: orthographic.area.height() / size.y,
+            },
+        });
+    }
+
+    /// `hit` along `ray`, with its distance measured from where a camera ray starts
+    fn ray_hit(&self, hit: MeshHit, entity: Entity) -> RayHit {
+        let mut hit = hit.into_ray_hit(entity, &self.ray);
+        hit.distance -= self.line_offset;
+        hit
+    }
+
     pub(crate) fn pos_from_camera(
         camera: &Camera,
         projection: &Projection,
         transform: &GlobalTransform,
         cursor_pos: Vec2, // [-1, 1]
+        settings: &PickingSettings,
     ) -> Ray {
-        // worldspace - position in 3d, with the global coordinate frame
-        // eyespace - position in 3d, with a coordinate frame centered on the camera
-        // imagespace - position in the 2d image
-
-        // position of the cursor, in imagespace [-1, 1]
-        let clip_space_pos = Vec3::new(cursor_pos.x, cursor_pos.y, 0.0);
-
-        // assuming the camera is at origin,
-        // `camera.projection_matrix()` transforms worldspace points into imagespace points
-        // when inverted, the matrix converts imagespace points into worldspace points
-        let inverse_projection = camera.projection_matrix().inverse();
-
-        match projection {
-            Projection::Perspective(_) => {
-                // transform cursor position from imagespace position into "eyespace"
-                let eye_space_pos = inverse_projection.transform_point3(clip_space_pos);
-                // but the camera can be at any position!
-                // transform the "eyespace" position to the true worldspace position
-                let world_space_pos = transform.compute_matrix() * eye_space_pos.extend(1.0);
-
-                Ray {
-                    // ray originates at the camera's focal point
-                    origin: transform.translation(),
-                    // ray extends from the focal point through the worldspace position of the
-                    // mouse cursor
-                    direction: (world_space_pos.truncate() - transform.translation()).normalize(),
-                }
+        let mut ray = screen_ndc_to_ray(camera, projection, transform, cursor_pos);
+        if let (Projection::Orthographic(_), false) = (projection, settings.ortho_full_line) {
+            // pushed back from the near plane, so objects behind it can be picked too
+            ray.origin -= ray.direction.normalize() * settings.ortho_near_offset;
+        }
+        ray
+    }
+}
+
+/// World space ray through `ndc`, a point in `camera`'s normalized device coordinates:
+/// (-1,-1) is the bottom left of its view and (1,1) is the upper right
+/// For rays through arbitrary points on screen, not just the cursor: a crosshair, a touch, a
+/// point projected from another camera
+///
+/// Perspective rays start at the camera's focal point, orthographic rays on its near plane,
+/// both point into the scene
+pub fn screen_ndc_to_ray(
+    camera: &Camera,
+    projection: &Projection,
+    transform: &GlobalTransform,
+    ndc: Vec2,
+) -> Ray {
+    // worldspace - position in 3d, with the global coordinate frame
+    // eyespace - position in 3d, with a coordinate frame centered on the camera
+    // imagespace - position in the 2d image
+
+    // position of the cursor, in imagespace [-1, 1]
+    let clip_space_pos = Vec3::new(ndc.x, ndc.y, 0.0);
+
+    // assuming the camera is at origin,
+    // `camera.projection_matrix()` transforms worldspace points into imagespace points
+    // when inverted, the matrix converts imagespace points into worldspace points
+    let inverse_projection = camera.projection_matrix().inverse();
+
+    match projection {
+        Projection::Perspective(_) => {
+            // transform cursor position from imagespace position into "eyespace"
+            let eye_space_pos = inverse_projection.transform_point3(clip_space_pos);
+            // but the camera can be at any position!
+            // transform the "eyespace" position to the true worldspace position
+            let world_space_pos = transform.compute_matrix() * eye_space_pos.extend(1.0);
+
+            Ray {
+                // ray originates at the camera's focal point
+                origin: transform.translation(),
+                // ray extends from the focal point through the worldspace position of the
+                // mouse cursor
+                direction: (world_space_pos.truncate() - transform.translation()).normalize(),
             }
-            Projection::Orthographic(_) => {
-                // same as the Prespective case, but ortho camera has no depth => disregard z component
-                let mut eye_space_pos = inverse_projection.transform_point3(clip_space_pos);
-                eye_space_pos.z = 0.0;
-                let m = transform.compute_matrix();
-                let world_space_pos = m * eye_space_pos.extend(1.0);
-
-                // an orthographic camera has no focal point or vanishing point
-                // in this case, camera rotation determines the ray direction
-                Ray {
-                    origin: world_space_pos.truncate(),
-                    // I honestly thought I understood this pretty well until this point
-                    // this is where we extract the rotation component of the camera's 4x4 3d affine
-                    // transform matrix, then multiply a unit vector by the resulting 3x3 matrix
-                    //
-                    // only the resulting rotated vector is always equivalent to the 3rd column of
-                    // the affine transform matrix * (-1)
-                    direction: -1.0 * m.z_axis.truncate(),
-                }
+        }
+        Projection::Orthographic(_) => {
+            // same as the Prespective case, but ortho camera has no depth: the whole ray lands on
+            // the cursor, start it where the view volume does, on the near plane
+            // bevy's depth is reversed, the near plane is at z = 1 in imagespace
+            let near_pos = clip_space_pos.truncate().extend(1.0);
+            let eye_space_pos = inverse_projection.transform_point3(near_pos);
+            let m = transform.compute_matrix();
+            let world_space_pos = m * eye_space_pos.extend(1.0);
+
+            Ray {
+                origin: world_space_pos.truncate(),
+                // I honestly thought I understood this pretty well until this point
+                // this is where we extract the rotation component of the camera's 4x4 3d affine
+                // transform matrix, then multiply a unit vector by the resulting 3x3 matrix
+                //
+                // only the resulting rotated vector is always equivalent to the 3rd column of
+                // the affine transform matrix * (-1)
+                direction: -1.0 * m.z_axis.truncate(),
             }
         }
     }
@@ -136,94 +1178,727 @@ fn add_mouse_ray(mut commands: Commands) {
     commands.spawn(MouseRay::default());
 }
 
+/// There's exactly one `MouseRay`, spawned by `add_mouse_ray`, and both `update_mouse_ray` and
+/// `update_hover_state` work on that one
+/// If there are more (e.g. the plugin's startup systems ran twice), picking is skipped: which ray
+/// the cursor should drive would be arbitrary
+fn report_mouse_rays(error: QuerySingleError) {
+    if let QuerySingleError::MultipleEntities(_) = error {
+        static WARNED: Once = Once::new();
+        WARNED.call_once(|| {
+            warn!("bevy-hover: found more than one MouseRay, picking is skipped until there's one")
+        });
+    }
+}
+
 fn add_resources(mut commands: Commands) {
-    commands.insert_resource(Hovered { inner: None });
+    commands.insert_resource(Hovered {
+        inner: None,
+        others: Vec::new(),
+    });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_mouse_ray(
     mut query: Query<&mut MouseRay>,
     windows: Query<&Window>,
     mut cursor_moved_events: EventReader<CursorMoved>,
-    camera_query: Query<(&Camera, &Projection, &GlobalTransform)>,
+    camera_query: Query<(&Camera, Ref<Projection>, Ref<GlobalTransform>, &RaySource)>,
+    settings: Res<PickingSettings>,
+    virtual_cursor: Res<VirtualCursor>,
+    ray_override: Res<PickRayOverride>,
+    mut pick_ray: ResMut<PickRay>,
 ) {
-    if let (Ok(window), Ok(mut mouse_ray)) = (windows.get_single(), query.get_single_mut()) {
-        for event in cursor_moved_events.read() {
-            let (camera, projection, camera_transform) = camera_query.single();
-            let cursor_pos = MouseRay::cursor_to_pos(&event.position, window);
-            let ray = MouseRay::pos_from_camera(camera, projection, camera_transform, cursor_pos);
-            mouse_ray.ray = ray;
+    #[cfg(feature = "trace")]
+    let _span = info_span!("update_mouse_ray").entered();
+    let mut mouse_ray = match query.get_single_mut() {
+        Ok(mouse_ray) => mouse_ray,
+        Err(error) => return report_mouse_rays(error),
+    };
+    if let Some(ray) = ray_override.0 {
+        mouse_ray.ray = ray.into();
+        // not cast from a camera, there are no clip planes
+        mouse_ray.clip_range = None;
+        mouse_ray.line_offset = 0.0;
+        mouse_ray.detached = false;
+        pick_ray.set_if_neq(ray);
+        return;
+    }
+    let mut cameras = camera_query
+        .iter()
+        .filter(|(.., source)| **source == RaySource::Mouse);
+    let (Some((camera, projection, camera_transform, _)), None) = (cameras.next(), cameras.next())
+    else {
+        // e.g. while a scene is loading, before its camera is spawned
+        mouse_ray.detached = true;
+        static WARNED: Once = Once::new();
+        WARNED.call_once(|| {
+            warn!(
+                "bevy-hover: expected exactly one RaySource::Mouse camera, picking is skipped \
+                 until there's one"
+            )
+        });
+        return;
+    };
+    mouse_ray.detached = false;
+
+    let cursor_pos = if let Some(cursor_pos) = virtual_cursor.0 {
+        // the virtual cursor overrides the real one, drop its events
+        cursor_moved_events.clear();
+        Some(cursor_pos)
+    } else {
+        windows.get_single().ok().and_then(|window| {
+            cursor_moved_events.read().last().map(|event| {
+                MouseRay::cursor_to_pos(
+                    &event.position,
+                    window,
+                    camera.logical_viewport_rect(),
+                    settings.cursor_convention,
+                )
+            })
+        })
+    };
+    let cursor_moved = match cursor_pos {
+        Some(cursor_pos) if mouse_ray.cursor != Some(cursor_pos) => {
+            mouse_ray.cursor = Some(cursor_pos);
+            true
         }
+        _ => false,
+    };
+    let camera_moved =
+        camera_transform.is_changed() || projection.is_changed() || settings.is_changed();
+    // back to the mouse after an override was lifted
+    let override_lifted = ray_override.is_changed();
+
+    // the ray only depends on the cursor and the camera, if neither changed it's still valid
+    // hover state doesn't get this shortcut: `update_hover_state` re-tests every frame, since
+    // the hoverables themselves may have moved under a still ray
+    if !(cursor_moved || camera_moved || override_lifted) {
+        return;
+    }
+    if let Some(cursor_pos) = mouse_ray.cursor {
+        mouse_ray.update(
+            camera,
+            &projection,
+            &camera_transform,
+            cursor_pos,
+            &settings,
+        );
+        pick_ray.set_if_neq(mouse_ray.ray.into());
     }
 }
 
+/// Progress of a time-sliced pick, see `PickingSettings::max_entities_per_frame`
+#[derive(Default)]
+struct PickSweep {
+    /// position in the hoverable query the next frame continues from
+    next: usize,
+    /// nearest hit found so far, across all frames of the current sweep
+    nearest: Option<(MeshHit, Entity)>,
+    /// hit on the currently hovered entity, for `PickingSettings::sticky`
+    current: Option<MeshHit>,
+    /// every hit of the current sweep, only collected for `PickingSettings::hover_band`
+    hits: Vec<(MeshHit, Entity)>,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn update_hover_state(
     mut commands: Commands,
-    mesh_assets: Res<Assets<Mesh>>,
+    // paired up to stay within the system parameter limit
+    (mesh_assets, materials): (Res<Assets<Mesh>>, Res<Assets<StandardMaterial>>),
     ray_query: Query<&MouseRay>,
     mut ev_hover_start: EventWriter<HoverStart>,
-    mut ev_hover_end: EventWriter<HoverEnd>,
-    query: Query<(&Handle<Mesh>, &GlobalTransform, Entity), With<Hoverable>>,
+    (mut ev_hover_end, mut ev_hover_move): (EventWriter<HoverEnd>, EventWriter<HoverMove>),
+    query: Query<PickTarget, With<Hoverable>>,
+    occluders: Query<PickTarget, (With<Occluder>, Without<Hoverable>)>,
     mut hovered: ResMut<Hovered>,
-    time: Res<Time>,
+    (time, frame, readback, mouse_buttons): (
+        Res<Time>,
+        Res<FrameCount>,
+        Res<ReadbackPick>,
+        Res<Input<MouseButton>>,
+    ),
+    settings: Res<PickingSettings>,
+    (mut sweep, mut last_starts): (Local<PickSweep>, Local<HashMap<Entity, Duration>>),
+    mut hovers: Query<&mut Hover>,
+    texels: TexelSampler,
+    (bounds, parents): (Res<MeshCache>, Query<&GlobalTransform>),
+    (ignored, capture, mut ray_pick): (Res<IgnoredEntities>, Res<PointerCapture>, ResMut<RayPick>),
+    mut stats: ResMut<PickingStats>,
 ) {
-    for ray in ray_query.iter() {
-        // Option<(distance, intersectee)>
-        let mut intersect_nearest: Option<(f32, Entity)> = None;
-
-        for (mesh_handle, transform, entity) in query.iter() {
-            if let Some(mesh) = mesh_assets.get(mesh_handle) {
-                let intersect = check_intersect(ray, mesh, transform);
-                match (intersect, intersect_nearest) {
-                    (Some(i), Some((i_n, _))) => {
-                        if i_n > i {
-                            intersect_nearest = Some((i, entity))
-                        }
+    #[cfg(feature = "trace")]
+    let _span = info_span!("update_hover_state").entered();
+    let pass_start = Instant::now();
+    let frame = frame.0;
+    let parent_of = |parent| parents.get(parent).ok().copied();
+    // counted by `bounds::update_mesh_bounds`, just before
+    let mut frame_stats = PickingStats {
+        meshes_built: stats.meshes_built,
+        ..default()
+    };
+    let layer = |entity: Entity| {
+        query
+            .get(entity)
+            .map(|target| target.layer())
+            .unwrap_or_default()
+    };
+    let texel = |entity: Entity, uv: Option<Vec2>| {
+        settings
+            .sample_texels
+            .then(|| texels.sample(entity, uv?))
+            .flatten()
+    };
+
+    // collected first and sent once the ray is resolved
+    let mut starts: Vec<HoverStart> = Vec::new();
+    let mut ends: Vec<HoverEnd> = Vec::new();
+    let mut moves: Vec<HoverMove> = Vec::new();
+    let moved = |hovered: Entity, hit: RayHit| HoverMove {
+        hovered,
+        hit,
+        buttons: mouse_buttons.get_pressed().copied().collect(),
+        frame,
+    };
+
+    let ray = match ray_query.get_single() {
+        Ok(ray) => ray,
+        Err(error) => return report_mouse_rays(error),
+    };
+    'resolve: {
+        if ray.detached {
+            // no camera, nothing can be under the cursor
+            *sweep = PickSweep::default();
+            ray_pick.0 = None;
+            let was_hovered: Vec<Entity> = hovered
+                .inner
+                .take()
+                .into_iter()
+                .chain(std::mem::take(&mut hovered.others))
+                .collect();
+            for entity in was_hovered {
+                commands.entity(entity).remove::<Hover>();
+                ends.push(HoverEnd {
+                    hovered: entity,
+                    last_point: last_point(&hovers, entity),
+                    frame,
+                });
+            }
+            break 'resolve;
+        }
+        let tolerance = ScreenTolerance::new(&settings, ray.pixel_size);
+        // a GPU backend already read back which entity is under the cursor, only it is tested
+        let readback = readback.0;
+        let (start, count) = match (settings.max_entities_per_frame, readback) {
+            (Some(budget), None) => (sweep.next, budget.max(1)),
+            _ => (0, usize::MAX),
+        };
+        sweep.next = start.saturating_add(count);
+        let hit_range = match (&ray.clip_range, settings.clamp_to_clip_planes) {
+            (Some(clip_range), true) => clip_range.clone(),
+            _ => 0.0..=f32::INFINITY,
+        };
+        let mut sweep_done = sweep.next >= query.iter().count();
+
+        // test front to back by bounds, so in `PickMode::Nearest` anything whose bounds start
+        // behind the nearest hit so far can be skipped without testing its triangles
+        // the hovered entity is always tested, `sticky` needs its hit
+        let mut targets: Vec<(f32, PickTargetItem)> = query
+            .iter()
+            .skip(start)
+            .take(count)
+            .filter(|target| !ignored.0.contains(&target.entity))
+            .filter(|target| readback.is_none_or(|hit| hit.entity == Some(target.entity)))
+            .filter_map(|target| {
+                let min_distance = match tolerance.radius {
+                    // near misses are hits too, outside the bounds
+                    Some(_) => Some(0.0),
+                    None => target
+                        .shape(&mesh_assets, Some(&*bounds), Some(&materials), &parent_of)
+                        .min_distance(&ray.ray),
+                };
+                if min_distance.is_none() {
+                    frame_stats.broad_phase_rejected += 1;
+                }
+                Some((min_distance?, target))
+            })
+            .collect();
+        let early_out = settings.pick_mode == PickMode::Nearest && settings.hover_band.is_none();
+        if early_out {
+            targets.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        }
+
+        for (min_distance, target) in targets {
+            let entity = target.entity;
+            if let (true, Some((nearest, e_n))) = (early_out, sweep.nearest) {
+                if min_distance > nearest.distance
+                    && target.layer() <= layer(e_n)
+                    && hovered.inner != Some(entity)
+                {
+                    frame_stats.broad_phase_rejected += 1;
+                    continue;
+                }
+            }
+            let shape = target.shape(&mesh_assets, Some(&*bounds), Some(&materials), &parent_of);
+            frame_stats.entities_tested += 1;
+            let intersect = match readback.and_then(|hit| hit.point) {
+                Some(point) => Some(MeshHit::at(ray.ray.origin.distance(point))),
+                None => {
+                    frame_stats.triangles_tested += shape.triangle_count();
+                    shape.intersect(&ray.ray, &settings, tolerance)
+                }
+            }
+            .filter(|hit| hit_range.contains(&hit.distance));
+            if hovered.inner == Some(entity) {
+                sweep.current = intersect;
+            }
+            if let (Some(hit), Some(_)) = (intersect, settings.hover_band) {
+                sweep.hits.push((hit, entity));
+            }
+            match (intersect, sweep.nearest) {
+                (Some(i), Some((i_n, e_n))) => {
+                    // a higher layer always wins, depth only counts within a layer
+                    let preferred = match target.layer().cmp(&layer(e_n)) {
+                        Ordering::Greater => true,
+                        Ordering::Less => false,
+                        Ordering::Equal => match settings.pick_mode {
+                            PickMode::ScreenClosest
+                                if (i.distance - i_n.distance).abs()
+                                    <= settings.screen_closest_margin =>
+                            {
+                                let offset =
+                                    |hit: &MeshHit| hit.screen_offset(&ray.ray, ray.pixel_size);
+                                offset(&i)
+                                    .total_cmp(&offset(&i_n))
+                                    .then(entity.cmp(&e_n))
+                                    .is_lt()
+                            }
+                            mode => mode.prefers((i.distance, entity), (i_n.distance, e_n)),
+                        },
+                    };
+                    if preferred {
+                        sweep.nearest = Some((i, entity))
                     }
-                    (Some(i), None) => intersect_nearest = Some((i, entity)),
-                    _ => (),
                 }
+                (Some(i), None) => sweep.nearest = Some((i, entity)),
+                _ => (),
+            }
+            if settings.pick_mode == PickMode::FirstHit && sweep.nearest.is_some() {
+                // good enough, no need to test the rest
+                sweep_done = true;
+                break;
             }
         }
-        if let Some((_, entity)) = intersect_nearest {
-            if let Some(prev_hover) = hovered.inner {
-                if prev_hover != entity {
-                    commands.entity(prev_hover).remove::<Hover>();
-                    ev_hover_end.send(HoverEnd {
-                        hovered: prev_hover,
-                    });
 
-                    commands.entity(entity).insert(Hover {
-                        since: time.elapsed(),
+        if !sweep_done {
+            // only part of the hoverables were tested this frame
+            // keep the current hover state until the rest are
+            if let (HoverMode::Polling, Some(entity)) = (settings.mode, hovered.inner) {
+                // re-sent with the last complete sweep's hit
+                if let Ok(hover) = hovers.get(entity) {
+                    starts.push(HoverStart {
+                        hovered: entity,
+                        ray: ray.ray,
+                        hit: hover.hit,
+                        texel: texel(entity, hover.hit.uv),
+                        frame,
                     });
-                    ev_hover_start.send(HoverStart { hovered: entity });
-                    hovered.inner = Some(entity);
                 }
-            } else {
-                commands.entity(entity).insert(Hover {
-                    since: time.elapsed(),
+            }
+            break 'resolve;
+        }
+        sweep.next = 0;
+        // Option<(hit, intersectee)>
+        let mut intersect_nearest = sweep.nearest.take();
+        if let Some(captured) = capture.0.filter(|entity| hovered.inner == Some(*entity)) {
+            // the captured entity keeps the hover whatever the ray hits, see `PointerCapture`
+            let current = sweep.current.take();
+            sweep.hits.clear();
+            ray_pick.0 = current.map(|_| captured);
+            if let (Some(hit), Ok(mut hover)) = (current, hovers.get_mut(captured)) {
+                let hit = ray.ray_hit(hit, captured);
+                if hover.hit.point != hit.point {
+                    moves.push(moved(captured, hit));
+                }
+                hover.hit = hit;
+            }
+            if let (HoverMode::Polling, Ok(hover)) = (settings.mode, hovers.get(captured)) {
+                starts.push(HoverStart {
+                    hovered: captured,
+                    ray: ray.ray,
+                    hit: hover.hit,
+                    texel: texel(captured, hover.hit.uv),
+                    frame,
                 });
-                ev_hover_start.send(HoverStart { hovered: entity });
-                hovered.inner = Some(entity);
             }
-        } else {
-            // no intersect, no entity currently hovered
-            if let Some(prev_hover) = hovered.inner {
+            break 'resolve;
+        }
+        if let (true, Some(current), Some(hovered_entity), Some((hit, picked))) = (
+            settings.sticky,
+            sweep.current.take(),
+            hovered.inner,
+            intersect_nearest,
+        ) {
+            // the hovered entity is still under the ray, only a clearly better hit (or one on a
+            // higher layer) takes over
+            if layer(hovered_entity) == layer(picked)
+                && (current.distance - hit.distance).abs() <= settings.sticky_margin
+            {
+                intersect_nearest = Some((current, hovered_entity));
+            }
+        }
+        sweep.current = None;
+        if let Some((hit, picked)) = intersect_nearest {
+            // the view is blocked if any occluder sits in front of the nearest hoverable,
+            // or on a higher layer than it
+            let picked_layer = layer(picked);
+            let occluded = occluders.iter().any(|occluder| {
+                let blocks = |o: &MeshHit| match occluder.layer().cmp(&picked_layer) {
+                    Ordering::Greater => true,
+                    Ordering::Less => false,
+                    Ordering::Equal => o.distance < hit.distance,
+                };
+                !ignored.0.contains(&occluder.entity)
+                    && occluder
+                        .shape(&mesh_assets, Some(&*bounds), Some(&materials), &parent_of)
+                        .intersect(&ray.ray, &settings, tolerance.lines_only())
+                        .is_some_and(|o| hit_range.contains(&o.distance) && blocks(&o))
+            });
+            if occluded {
+                intersect_nearest = None;
+            }
+        }
+        let hits = std::mem::take(&mut sweep.hits);
+        // the picked entity first, then the others within `hover_band` of it
+        let hovering: Vec<(MeshHit, Entity)> = intersect_nearest
+            .into_iter()
+            .chain(hits.into_iter().filter(|(hit, entity)| {
+                let (Some(band), Some((nearest, picked))) =
+                    (settings.hover_band, intersect_nearest)
+                else {
+                    return false;
+                };
+                *entity != picked
+                    && layer(*entity) == layer(picked)
+                    && (hit.distance - nearest.distance).abs() <= band
+            }))
+            .collect();
+        // ends and starts are the difference between what was hovered and what is now, entity
+        // by entity, so an entity that stays hovered while others come and go gets neither
+        let was_hovered: Vec<Entity> = hovered
+            .inner
+            .into_iter()
+            .chain(hovered.others.iter().copied())
+            .collect();
+        let now_hovered: HashSet<Entity> = hovering.iter().map(|(_, entity)| *entity).collect();
+
+        // all ends before any starts, see `HoverStart`
+        for &prev_hover in &was_hovered {
+            if !now_hovered.contains(&prev_hover) {
                 commands.entity(prev_hover).remove::<Hover>();
-                ev_hover_end.send(HoverEnd {
+                ends.push(HoverEnd {
                     hovered: prev_hover,
+                    last_point: last_point(&hovers, prev_hover),
+                    frame,
                 });
-                hovered.inner = None;
             }
         }
+        for &(hit, entity) in &hovering {
+            let hit = ray.ray_hit(hit, entity);
+            let entered = !was_hovered.contains(&entity);
+            if entered {
+                commands.entity(entity).insert(Hover {
+                    since: time.elapsed(),
+                    hit,
+                });
+            } else if let Ok(mut hover) = hovers.get_mut(entity) {
+                if hover.hit.point != hit.point {
+                    moves.push(moved(entity, hit));
+                }
+                hover.hit = hit;
+            }
+            if entered || settings.mode == HoverMode::Polling {
+                starts.push(HoverStart {
+                    hovered: entity,
+                    ray: ray.ray,
+                    hit,
+                    texel: texel(entity, hit.uv),
+                    frame,
+                });
+            }
+        }
+        let inner = hovering.first().map(|(_, entity)| *entity);
+        ray_pick.0 = inner;
+        let others: Vec<Entity> = hovering.iter().skip(1).map(|(_, entity)| *entity).collect();
+        // only marks `Hovered` changed if it actually did
+        if hovered.inner != inner || hovered.others != others {
+            hovered.inner = inner;
+            hovered.others = others;
+        }
+    }
+    frame_stats.time_us = pass_start.elapsed().as_micros() as u64;
+    *stats = frame_stats;
+
+    // all ends before any starts, see `HoverStart`
+    // with the one ray, each entity is in each at most once
+    ev_hover_end.send_batch(ends);
+    ev_hover_move.send_batch(moves);
+    let now = time.elapsed();
+    if let Some(cooldown) = settings.hover_cooldown {
+        last_starts.retain(|_, last| now.saturating_sub(*last) < cooldown);
+    }
+    for start in starts {
+        if settings.hover_cooldown.is_some() {
+            if last_starts.contains_key(&start.hovered) {
+                // still cooling down from the last one
+                continue;
+            }
+            last_starts.insert(start.hovered, now);
+        }
+        ev_hover_start.send(start);
     }
 }
 
+fn load_collider_sources(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    sources: Query<(Entity, &HoverColliderSource), Changed<HoverColliderSource>>,
+) {
+    for (entity, source) in sources.iter() {
+        let mesh: Handle<Mesh> = asset_server.load(source.path.clone());
+        commands.entity(entity).insert(HoverLod(mesh));
+    }
+}
+
+fn sync_current_hover(
+    mut commands: Commands,
+    hovered: Res<Hovered>,
+    mut sources: Query<(Entity, &RaySource, Option<&mut CurrentHover>)>,
+) {
+    for (entity, _, current) in sources
+        .iter_mut()
+        .filter(|(_, source, _)| **source == RaySource::Mouse)
+    {
+        match current {
+            // only marks the component changed if the hovered entity actually changed
+            Some(mut current) => {
+                current.set_if_neq(CurrentHover(hovered.inner));
+            }
+            None => {
+                commands.entity(entity).insert(CurrentHover(hovered.inner));
+            }
+        }
+    }
+}
+
+fn send_hover_changed(
+    hovered: Res<Hovered>,
+    settings: Res<PickingSettings>,
+    frame: Res<FrameCount>,
+    // None until the first run
+    mut last: Local<Option<Option<Entity>>>,
+    mut ev_hover_changed: EventWriter<HoverChanged>,
+) {
+    let from = last.flatten();
+    let first_run = last.is_none();
+    if hovered.inner != from || (first_run && settings.initial_hover_changed) {
+        ev_hover_changed.send(HoverChanged {
+            from,
+            to: hovered.inner,
+            frame: frame.0,
+        });
+    }
+    *last = Some(hovered.inner);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_cursor_over_bounds(
+    ray_query: Query<&MouseRay>,
+    mesh_assets: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    targets: Query<PickTarget, With<Hoverable>>,
+    settings: Res<PickingSettings>,
+    bounds: Res<MeshCache>,
+    parents: Query<&GlobalTransform>,
+    ignored: Res<IgnoredEntities>,
+    mut over: ResMut<CursorOverBounds>,
+) {
+    let parent_of = |parent| parents.get(parent).ok().copied();
+    let over_any = ray_query.get_single().is_ok_and(|ray| {
+        !ray.detached
+            && targets
+                .iter()
+                .filter(|target| !ignored.0.contains(&target.entity))
+                .any(|target| {
+                    target
+                        .shape(&mesh_assets, Some(&*bounds), Some(&materials), &parent_of)
+                        .hits_bounds(&ray.ray, &settings)
+                })
+    });
+    over.set_if_neq(CursorOverBounds(over_any));
+}
+
+/// Points each `RaySource::Forward`'s `CurrentHover` at the nearest `Hoverable` along its
+/// forward axis, or at nothing if an `Occluder` is in the way
+/// A source never hits itself, so it can be a hoverable mesh too
+#[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
+fn update_forward_sources(
+    mut commands: Commands,
+    mesh_assets: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut sources: Query<(
+        Entity,
+        &RaySource,
+        &GlobalTransform,
+        Option<&mut CurrentHover>,
+    )>,
+    targets: Query<(PickTarget, Has<Hoverable>), Or<(With<Hoverable>, With<Occluder>)>>,
+    settings: Res<PickingSettings>,
+    bounds: Res<MeshCache>,
+    parents: Query<&GlobalTransform>,
+    ignored: Res<IgnoredEntities>,
+) {
+    let parent_of = |parent| parents.get(parent).ok().copied();
+    for (source, kind, transform, current) in sources.iter_mut() {
+        if *kind != RaySource::Forward {
+            continue;
+        }
+        let ray = Ray {
+            origin: transform.translation(),
+            direction: transform.forward(),
+        };
+        let nearest = targets
+            .iter()
+            .filter(|(target, _)| target.entity != source && !ignored.0.contains(&target.entity))
+            .filter_map(|(target, hoverable)| {
+                let hit = target
+                    .shape(&mesh_assets, Some(&*bounds), Some(&materials), &parent_of)
+                    .intersect(&ray, &settings, ScreenTolerance::default())?;
+                Some((hit.distance, target.entity, hoverable))
+            })
+            .min_by(|a, b| hit_order((a.0, a.1), (b.0, b.1)));
+        let hovered =
+            CurrentHover(nearest.and_then(|(_, entity, hoverable)| hoverable.then_some(entity)));
+        match current {
+            Some(mut current) => {
+                current.set_if_neq(hovered);
+            }
+            None => {
+                commands.entity(source).insert(hovered);
+            }
+        }
+    }
+}
+
+/// Where the ray last hit `entity`, from its `Hover`
+fn last_point(hovers: &Query<&mut Hover>, entity: Entity) -> Vec3 {
+    // `Hover` is only missing if it was removed by hand, there's no better answer then
+    hovers
+        .get(entity)
+        .map(|hover| hover.hit.point)
+        .unwrap_or_default()
+}
+
+/// Orders hits nearest first
+/// hits at exactly the same distance (e.g. coincident faces) are ordered by `Entity`, so the
+/// order doesn't depend on query iteration order and stays the same from frame to frame
+fn hit_order((a_dist, a): (f32, Entity), (b_dist, b): (f32, Entity)) -> Ordering {
+    a_dist.total_cmp(&b_dist).then(a.cmp(&b))
+}
+
+/// Every `Hoverable` that `ray` hits
+/// Sorted nearest first, with ties broken by `Entity`, see `hit_order`
+/// For one-off queries from exclusive systems or `Commands::add`, tested the same way (and with
+/// the same `PickingSettings` and `IgnoredEntities`) as the hover pass
+pub fn cast_ray(world: &mut World, ray: Ray) -> Vec<RayHit> {
+    cast_ray_filtered::<With<Hoverable>>(world, ray)
+}
+
+/// Like `cast_ray`, but tests every entity with a mesh (or collider, or intersector) that matches
+/// `F` instead of every `Hoverable`, e.g. `cast_ray_filtered::<With<Enemy>>(world, ray)`
+/// `IgnoredEntities` still applies
+pub fn cast_ray_filtered<F: ReadOnlyWorldQuery>(world: &mut World, ray: Ray) -> Vec<RayHit> {
+    let mut query = world.query_filtered::<PickTarget, F>();
+    let world = &*world;
+    let mesh_assets = world.resource::<Assets<Mesh>>();
+    let settings = world.resource::<PickingSettings>();
+    let bounds = world.get_resource::<MeshCache>();
+    let ignored = world.get_resource::<IgnoredEntities>();
+
+    let mut hits: Vec<RayHit> = query
+        .iter(world)
+        .filter(|target| !ignored.is_some_and(|ignored| ignored.0.contains(&target.entity)))
+        .filter_map(|target| {
+            let hit = target
+                .shape(mesh_assets, bounds, world.get_resource(), &|parent| {
+                    world.get::<GlobalTransform>(parent).copied()
+                })
+                .intersect(&ray, settings, ScreenTolerance::default())?;
+            Some(hit.into_ray_hit(target.entity, &ray))
+        })
+        .collect();
+    hits.sort_by(|a, b| hit_order((a.distance, a.entity), (b.distance, b.entity)));
+    hits
+}
+
+/// Nearest `Hoverable` under the cursor right now, for input handlers that need it immediately
+/// rather than from next frame's hover events
+/// Casts through the `VirtualCursor`, or the window's cursor, from the `RaySource::Mouse` camera
+/// (or along `PickRayOverride`) with `cast_ray`, so occluders and `HoverLayer`s don't apply
+/// Tests against the `GlobalTransform`s of the last transform propagation, except for entities
+/// without a parent spawned or moved since, e.g. earlier in this frame's `Update`, which are
+/// picked at their `Transform`. Children moved since are still picked where they were last frame
+pub fn pick_under_cursor(world: &mut World) -> Option<RayHit> {
+    let ray = cursor_ray(world)?;
+    cast_ray(world, ray).into_iter().next()
+}
+
+/// The ray `pick_under_cursor` casts, None without a cursor or a camera to cast from
+fn cursor_ray(world: &mut World) -> Option<Ray> {
+    if let Some(ray) = world.resource::<PickRayOverride>().0 {
+        return Some(ray.into());
+    }
+    // made before the cameras are borrowed from `world`
+    let mut windows = world.query::<&Window>();
+    let mut cameras = world.query::<(&Camera, &Projection, &GlobalTransform, &RaySource)>();
+    let (camera, projection, transform, _) = cameras
+        .iter(world)
+        .find(|(.., source)| **source == RaySource::Mouse)?;
+    let cursor = match world.resource::<VirtualCursor>().0 {
+        Some(cursor) => cursor,
+        None => {
+            let window = windows.get_single(world).ok()?;
+            MouseRay::cursor_to_pos(
+                &window.cursor_position()?,
+                window,
+                camera.logical_viewport_rect(),
+                // straight from the window, not a `CursorMoved` with a custom convention
+                CursorConvention::default(),
+            )
+        }
+    };
+    let settings = world.resource::<PickingSettings>();
+    Some(MouseRay::pos_from_camera(
+        camera, projection, transform, cursor, settings,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn update_hover_press(
     mouse_button_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
     query: Query<Entity, With<Hover>>,
     mut ev_hover_press: EventWriter<HoverPress>,
     mut ev_hover_release: EventWriter<HoverRelease>,
+    mut ev_hover_click: EventWriter<HoverClick>,
+    mut ev_press_cancel: EventWriter<HoverPressCancel>,
+    hovered: Res<Hovered>,
+    mut pressed: Local<HashMap<MouseButton, Entity>>,
+    mut key_pressed: Local<Option<Entity>>,
+    mut focus_events: EventReader<WindowFocused>,
+    focused: Res<Focused>,
+    settings: Res<PickingSettings>,
+    ray_pick: Res<RayPick>,
 ) {
     for entity in &query {
         for button in mouse_button_input.get_just_pressed() {
@@ -233,63 +1908,799 @@ fn update_hover_press(
             ev_hover_release.send(HoverRelease{ entity, button: *button });
         }
     }
+
+    // the release of a press that's in flight when the window loses focus (alt-tab) may never
+    // arrive, cancel it now instead of clicking (or not) whenever the button is next released
+    if focus_events.read().any(|event| !event.focused) {
+        for (button, entity) in pressed.drain() {
+            ev_press_cancel.send(HoverPressCancel { entity, button });
+        }
+        if let Some(entity) = key_pressed.take() {
+            ev_press_cancel.send(HoverPressCancel {
+                entity,
+                button: MouseButton::Left,
+            });
+        }
+    }
+
+    // keyboard activation acts as the left button on whatever the keyboard targets
+    // a press lasts while any activation key is held, so rolling from one to another doesn't
+    // click twice
+    let target = focused.0.or(hovered.inner);
+    let key_held = keys.any_pressed(settings.activation_keys.iter().copied());
+    match (*key_pressed, key_held) {
+        (None, true) => {
+            if let Some(entity) = target {
+                ev_hover_press.send(HoverPress {
+                    entity,
+                    button: MouseButton::Left,
+                });
+                *key_pressed = Some(entity);
+            }
+        }
+        (Some(entity), false) => {
+            *key_pressed = None;
+            let button = MouseButton::Left;
+            ev_hover_release.send(HoverRelease { entity, button });
+            if target == Some(entity) {
+                ev_hover_click.send(HoverClick { entity, button });
+            } else {
+                ev_press_cancel.send(HoverPressCancel { entity, button });
+            }
+        }
+        _ => (),
+    }
+
+    // remember what each button went down on, to tell clicks from cancelled presses
+    if let Some(entity) = hovered.inner {
+        for button in mouse_button_input.get_just_pressed() {
+            pressed.insert(*button, entity);
+        }
+    }
+    for button in mouse_button_input.get_just_released() {
+        let Some(entity) = pressed.remove(button) else {
+            continue;
+        };
+        // not `Hover`: a captured entity keeps it after the cursor has left
+        if ray_pick.0 == Some(entity) {
+            ev_hover_click.send(HoverClick {
+                entity,
+                button: *button,
+            });
+        } else {
+            ev_press_cancel.send(HoverPressCancel {
+                entity,
+                button: *button,
+            });
+        }
+    }
 }
 
+/// A press on a hoverable that may turn into a drag
+struct ActiveDrag {
+    entity: Entity,
+    button: MouseButton,
+    /// where the pick ray last crossed the drag plane
+    last_point: Option<Vec3>,
+    /// `DragStart` was sent
+    started: bool,
+}
 
-/// Some(distance) if there is an intersection
-/// None otherwise
-fn check_intersect(ray: &MouseRay, mesh: &Mesh, transform: &GlobalTransform) -> Option<f32> {
-    if let Some(VertexAttributeValues::Float32x3(vertex_positions)) =
-        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-    {
-        let inner_fn = |indices: &Vec<u32>| {
-            let mut min_dist: Option<f32> = None;
-            for tri in indices.chunks_exact(3) {
-                let v0 = Vec3::from(vertex_positions[tri[0] as usize]);
-                let v1 = Vec3::from(vertex_positions[tri[1] as usize]);
-                let v2 = Vec3::from(vertex_positions[tri[2] as usize]);
-
-                // Transform the vertices from model space to world space
-                let mat = transform.compute_matrix();
-                let v0 = mat.transform_point3(v0);
-                let v1 = mat.transform_point3(v1);
-                let v2 = mat.transform_point3(v2);
-
-                // Use Moller-Trumbore algorithm here to check for intersection
-                let dist = moller_trumbore(ray.ray.origin, ray.ray.direction, v0, v1, v2);
-                match (dist, min_dist) {
-                    (Some(d), Some(md)) if md > d => min_dist = Some(d),
-                    (Some(d), None) => min_dist = Some(d),
-                    _ => (),
-                };
+#[allow(clippy::too_many_arguments)]
+fn update_drag(
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut presses: EventReader<HoverPress>,
+    mut focus_events: EventReader<WindowFocused>,
+    pick_ray: Res<PickRay>,
+    settings: Res<PickingSettings>,
+    mut capture: ResMut<PointerCapture>,
+    mut active: Local<Option<ActiveDrag>>,
+    mut ev_drag_start: EventWriter<DragStart>,
+    mut ev_drag: EventWriter<Drag>,
+    mut ev_drag_end: EventWriter<DragEnd>,
+) {
+    let focus_lost = focus_events.read().any(|event| !event.focused);
+    if let Some(drag) = active.as_ref() {
+        if focus_lost || mouse_button_input.just_released(drag.button) {
+            if drag.started {
+                ev_drag_end.send(DragEnd {
+                    entity: drag.entity,
+                    button: drag.button,
+                });
             }
-            min_dist
+            *active = None;
+        }
+    }
+    let all_released = mouse_button_input.get_just_released().next().is_some()
+        && mouse_button_input.get_pressed().next().is_none();
+    if focus_lost || all_released {
+        capture.0 = None;
+    }
+
+    // one drag at a time, further presses during it are ignored
+    // so are presses from keyboard activation, there's no held button to drag with
+    for press in presses.read() {
+        if active.is_none() && mouse_button_input.pressed(press.button) {
+            *active = Some(ActiveDrag {
+                entity: press.entity,
+                button: press.button,
+                last_point: settings.drag_plane.intersect((*pick_ray).into()),
+                started: false,
+            });
+            // captured from the press rather than `DragStart`, the hover would already have
+            // ended by the time the cursor has moved far enough to start the drag
+            capture.0 = Some(press.entity);
+        }
+    }
+
+    let Some(drag) = active.as_mut() else {
+        return;
+    };
+    if !pick_ray.is_changed() {
+        return;
+    }
+    // a ray parallel to the plane doesn't cross it, skip this frame's movement, it's picked up
+    // by the next delta instead
+    let Some(point) = settings.drag_plane.intersect((*pick_ray).into()) else {
+        return;
+    };
+    if let Some(last_point) = drag.last_point.replace(point) {
+        if point == last_point {
+            return;
+        }
+        if !drag.started {
+            drag.started = true;
+            ev_drag_start.send(DragStart {
+                entity: drag.entity,
+                button: drag.button,
+            });
+        }
+        ev_drag.send(Drag {
+            entity: drag.entity,
+            button: drag.button,
+            delta: point - last_point,
+        });
+    }
+}
+
+/// Callback run with the pressed entity, see `HoverAppExt::add_hover_press_handler`
+type HoverPressHandler = Arc<dyn Fn(Entity, &mut World) + Send + Sync>;
+
+#[derive(Resource, Default)]
+struct HoverPressHandlers(Vec<HoverPressHandler>);
+
+pub trait HoverAppExt {
+    /// Runs `handler` whenever any hoverable is pressed, with the pressed entity
+    /// Saves writing a `HoverPress` reader system for one-off reactions, like a click sound
+    /// Handlers run in an exclusive system after the press events are sent, in registration order
+    fn add_hover_press_handler(
+        &mut self,
+        handler: impl Fn(Entity, &mut World) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl HoverAppExt for App {
+    fn add_hover_press_handler(
+        &mut self,
+        handler: impl Fn(Entity, &mut World) + Send + Sync + 'static,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<HoverPressHandlers>() {
+            self.init_resource::<HoverPressHandlers>()
+                .add_systems(Update, run_hover_press_handlers.after(update_hover_press));
+        }
+        self.world
+            .resource_mut::<HoverPressHandlers>()
+            .0
+            .push(Arc::new(handler));
+        self
+    }
+}
+
+fn run_hover_press_handlers(world: &mut World, mut reader: Local<ManualEventReader<HoverPress>>) {
+    let pressed: Vec<Entity> = reader
+        .read(world.resource::<Events<HoverPress>>())
+        .map(|ev| ev.entity)
+        .collect();
+    if pressed.is_empty() {
+        return;
+    }
+
+    // cloned out, so handlers are free to use the world (even to register more handlers)
+    let handlers = world.resource::<HoverPressHandlers>().0.clone();
+    for entity in pressed {
+        for handler in &handlers {
+            handler(entity, world);
+        }
+    }
+}
+
+
+/// Where a ray hit a mesh, before it's known which entity the mesh belongs to, see `RayHit`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MeshHit {
+    /// world space distance from the ray origin
+    distance: f32,
+    /// texture coordinates at the hit, None if the mesh has no `ATTRIBUTE_UV_0`
+    uv: Option<Vec2>,
+    normal: Option<Vec3>,
+    triangle: Option<[usize; 3]>,
+    barycentric: Option<Vec3>,
+    /// world space
+    vertices: Option<[Vec3; 3]>,
+}
+
+impl MeshHit {
+    /// Hit with nothing known but its distance
+    fn at(distance: f32) -> Self {
+        Self {
+            distance,
+            uv: None,
+            normal: None,
+            triangle: None,
+            barycentric: None,
+            vertices: None,
+        }
+    }
+
+    /// How far the center of the hit triangle is from `ray` on screen, in pixels (in world units
+    /// without a `pixel_size`), see `PickMode::ScreenClosest`
+    /// 0 for hits that aren't on a triangle
+    fn screen_offset(&self, ray: &Ray, pixel_size: Option<PixelSize>) -> f32 {
+        let Some([v0, v1, v2]) = self.vertices else {
+            return 0.0;
         };
+        let center = (v0 + v1 + v2) / 3.0;
+        let direction = ray.direction.normalize();
+        let along = (center - ray.origin).dot(direction);
+        let offset = (center - ray.origin - direction * along).length();
+        match pixel_size.map(|pixel_size| pixel_size.at(along)) {
+            Some(pixel) if pixel > 0.0 => offset / pixel,
+            _ => offset,
+        }
+    }
+
+    pub(crate) fn into_ray_hit(self, entity: Entity, ray: &Ray) -> RayHit {
+        RayHit {
+            entity,
+            distance: self.distance,
+            point: ray.get_point(self.distance),
+            normal: self.normal,
+            uv: self.uv,
+            triangle: self.triangle,
+            barycentric: self.barycentric,
+            vertices: self.vertices,
+        }
+    }
+}
 
-        match mesh.indices() {
-            Some(bevy::render::mesh::Indices::U32(indices)) => inner_fn(indices),
-            // TODO: very bad, clones mesh so I can avoid copy-pasting inner_fn
-            Some(bevy::render::mesh::Indices::U16(indices)) => {
-                inner_fn(&indices.iter().map(|x| *x as u32).collect())
+/// A `HoverIntersector`'s hit, its entity and point are filled back in by `into_ray_hit`
+impl From<RayHit> for MeshHit {
+    fn from(hit: RayHit) -> Self {
+        Self {
+            distance: hit.distance,
+            uv: hit.uv,
+            normal: hit.normal,
+            triangle: hit.triangle,
+            barycentric: hit.barycentric,
+            vertices: hit.vertices,
+        }
+    }
+}
+
+/// Some(hit) if there is an intersection
+/// None otherwise
+/// `cpu_mesh` is `mesh`'s cached copy, the triangles are read from `mesh` itself without one
+#[allow(clippy::too_many_arguments)]
+fn check_intersect(
+    ray: &Ray,
+    mesh: &Mesh,
+    cpu_mesh: Option<&CpuMesh>,
+    bounds: Option<ModelBounds>,
+    transform: &GlobalTransform,
+    cull_mode: Option<Face>,
+    min_triangle: Option<(PixelSize, f32)>,
+    settings: &PickingSettings,
+) -> Option<MeshHit> {
+    #[cfg(feature = "trace")]
+    let _span = info_span!("check_intersect").entered();
+    // test in model space: transforming the ray once is much cheaper than transforming every
+    // vertex into world space
+    let local_ray = LocalRay::new(ray, transform)?;
+    let (local_origin, local_direction) = (local_ray.origin, local_ray.direction);
+    if bounds.is_some_and(|bounds| !bounds.intersects(local_origin, local_direction)) {
+        return None;
+    }
+
+    let (vertex_positions, triangles) = match cpu_mesh {
+        Some(cpu_mesh) => (Cow::Borrowed(&cpu_mesh.positions[..]), cpu_mesh.triangles()),
+        None => (vertex_positions(mesh)?, mesh_triangle_indices(mesh)?),
+    };
+    let vertex = |idx: usize| Vec3::from(vertex_positions[idx]);
+
+    // (distance, triangle indices)
+    let mut min_dist: Option<(f32, [usize; 3])> = None;
+    for tri in triangles {
+        if let Some(face) = cull_mode {
+            // counter-clockwise triangles face the ray when it points against their normal
+            // tested in model space, so a mirroring transform (negative determinant), which
+            // flips the winding seen in world space, still culls the same faces as unmirrored
+            let [v0, v1, v2] = tri.map(vertex);
+            let front = local_direction.dot((v1 - v0).cross(v2 - v0)) < 0.0;
+            if front == (face == Face::Front) {
+                continue;
             }
-            None => None,
         }
+        // Use Moller-Trumbore algorithm here to check for intersection
+        let dist = moller_trumbore(
+            local_origin,
+            local_direction,
+            vertex(tri[0]),
+            vertex(tri[1]),
+            vertex(tri[2]),
+            settings.epsilon,
+            settings.near_epsilon,
+        );
+        let dist = dist.filter(|t| {
+            min_triangle.is_none_or(|(pixel_size, pixels)| {
+                // area of the triangle as seen along the ray, against the area of `pixels`
+                // pixels at the hit, close to its area on screen
+                let [v0, v1, v2] = tri.map(|idx| transform.transform_point(vertex(idx)));
+                let direction = ray.direction.normalize();
+                let area = (v1 - v0).cross(v2 - v0).dot(direction).abs() / 2.0;
+                let pixel = pixel_size.at(local_ray.world_distance(*t, ray));
+                area >= pixels * pixel * pixel
+            })
+        });
+        match (dist, min_dist) {
+            (Some(d), Some((md, _))) if md > d => min_dist = Some((d, tri)),
+            (Some(d), None) => min_dist = Some((d, tri)),
+            _ => (),
+        };
+    }
+
+    let (t, tri) = min_dist?;
+    let [v0, v1, v2] = tri.map(vertex);
+    let local_point = local_ray.point(t);
+    let barycentric = barycentric(local_point, v0, v1, v2);
+    // normals transform with the inverse transpose, so they stay perpendicular to the surface
+    // under non-uniform scale
+    let normal_matrix = Mat3::from_mat4(transform.compute_matrix())
+        .inverse()
+        .transpose();
+    let normal = (normal_matrix * (v1 - v0).cross(v2 - v0)).try_normalize();
+    Some(MeshHit {
+        distance: local_ray.world_distance(t, ray),
+        uv: hit_uv(mesh, tri, barycentric),
+        normal,
+        triangle: Some(tri),
+        barycentric: Some(barycentric),
+        vertices: Some([v0, v1, v2].map(|v| transform.transform_point(v))),
+    })
+}
+
+/// Some(hit) if the ray passes within `tolerance` of one of `mesh`'s line segments
+/// Tested in world space, since the tolerance is in world units
+fn check_line_intersect(
+    ray: &Ray,
+    mesh: &Mesh,
+    transform: &GlobalTransform,
+    tolerance: PixelSize,
+    settings: &PickingSettings,
+) -> Option<MeshHit> {
+    let vertex_positions = vertex_positions(mesh)?;
+    let vertex = |idx: usize| transform.transform_point(Vec3::from(vertex_positions[idx]));
+
+    let distance = mesh_line_indices(mesh, vertex_positions.len())
+        .filter_map(|[i0, i1]| {
+            let (t, gap) = ray_segment_closest(ray, vertex(i0), vertex(i1))?;
+            (t > settings.near_epsilon && gap <= tolerance.at(t)).then_some(t)
+        })
+        .min_by(f32::total_cmp)?;
+    Some(MeshHit::at(distance))
+}
+
+/// Some(hit) if the ray passes within `tolerance` of one of the edges of `mesh`'s triangles
+/// For near misses of triangle meshes, see `PickingSettings::pick_radius_pixels`
+fn check_edge_intersect(
+    ray: &Ray,
+    mesh: &Mesh,
+    cpu_mesh: Option<&CpuMesh>,
+    transform: &GlobalTransform,
+    tolerance: PixelSize,
+    settings: &PickingSettings,
+) -> Option<MeshHit> {
+    let (vertex_positions, triangles) = match cpu_mesh {
+        Some(cpu_mesh) => (Cow::Borrowed(&cpu_mesh.positions[..]), cpu_mesh.triangles()),
+        None => (vertex_positions(mesh)?, mesh_triangle_indices(mesh)?),
+    };
+    let vertex = |idx: usize| transform.transform_point(Vec3::from(vertex_positions[idx]));
+
+    let distance = triangles
+        .flat_map(|[i0, i1, i2]| [[i0, i1], [i1, i2], [i2, i0]])
+        .filter_map(|[i0, i1]| {
+            let (t, gap) = ray_segment_closest(ray, vertex(i0), vertex(i1))?;
+            (t > settings.near_epsilon && gap <= tolerance.at(t)).then_some(t)
+        })
+        .min_by(f32::total_cmp)?;
+    Some(MeshHit::at(distance))
+}
+
+/// Vertex indices of every segment in a `LineList` or `LineStrip` mesh, indexed or not
+fn mesh_line_indices(
+    mesh: &Mesh,
+    vertex_count: usize,
+) -> Box<dyn Iterator<Item = [usize; 2]> + '_> {
+    let mut indices: Box<dyn Iterator<Item = usize>> = match mesh.indices() {
+        Some(indices) => Box::new(indices.iter()),
+        None => Box::new(0..vertex_count),
+    };
+    match mesh.primitive_topology() {
+        PrimitiveTopology::LineList => Box::new(std::iter::from_fn(move || {
+            Some([indices.next()?, indices.next()?])
+        })),
+        _ => {
+            let mut prev = indices.next();
+            Box::new(std::iter::from_fn(move || {
+                let next = indices.next()?;
+                Some([prev.replace(next)?, next])
+            }))
+        }
+    }
+}
+
+/// Closest approach of `ray` to the segment from `a` to `b`
+/// Returns the distance along the ray to its closest point, and how far that point is from the
+/// segment; None if `ray.direction` is zero
+fn ray_segment_closest(ray: &Ray, a: Vec3, b: Vec3) -> Option<(f32, f32)> {
+    let d = ray.direction;
+    let e = b - a;
+    let w = ray.origin - a;
+    let (dd, de, ee) = (d.dot(d), d.dot(e), e.dot(e));
+    let (dw, ew) = (d.dot(w), e.dot(w));
+    if dd == 0.0 {
+        return None;
+    }
+    // minimize |origin + t * d - (a + s * e)| over t, with s clamped to the segment
+    let denom = dd * ee - de * de;
+    let s = if denom > 0.0 {
+        ((dd * ew - de * dw) / denom).clamp(0.0, 1.0)
     } else {
-        None
+        0.0 // parallel (or a zero length segment): any point will do
+    };
+    let t = (de * s - dw) / dd;
+    let gap = ray.get_point(t).distance(a + e * s);
+    Some((t, gap))
+}
+
+/// A world space ray moved into an entity's model space
+pub(crate) struct LocalRay {
+    /// model to world
+    mat: Mat4,
+    pub(crate) origin: Vec3,
+    /// deliberately not normalized, so `near_epsilon` keeps (roughly) meaning world units
+    pub(crate) direction: Vec3,
+}
+
+impl LocalRay {
+    /// None if `transform` can't be inverted (zero scale), there's nothing to hit then
+    pub(crate) fn new(ray: &Ray, transform: &GlobalTransform) -> Option<Self> {
+        let mat = transform.compute_matrix();
+        if mat.determinant() == 0.0 {
+            return None;
+        }
+        let inverse = mat.inverse();
+        Some(Self {
+            mat,
+            origin: inverse.transform_point3(ray.origin),
+            direction: inverse.transform_vector3(ray.direction),
+        })
+    }
+
+    pub(crate) fn point(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// World space distance from `ray`'s origin to the local hit at `t`
+    /// `t` is in model space units, how those stretch into world units depends on the
+    /// direction under non-uniform scale, so don't scale it by a single factor
+    /// move the hit point back to world space and measure from the ray origin instead
+    pub(crate) fn world_distance(&self, t: f32, ray: &Ray) -> f32 {
+        self.mat
+            .transform_point3(self.point(t))
+            .distance(ray.origin)
+    }
+}
+
+/// Barycentric coordinates of `p` with respect to the triangle (a, b, c)
+/// `p` is assumed to lie in the triangle's plane
+fn barycentric(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d00 = ab.dot(ab);
+    let d01 = ab.dot(ac);
+    let d11 = ac.dot(ac);
+    let d20 = ap.dot(ab);
+    let d21 = ap.dot(ac);
+    let denom = d00 * d11 - d01 * d01;
+    if denom == 0.0 {
+        return Vec3::X; // degenerate triangle, all weight on a
+    }
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    Vec3::new(1.0 - v - w, v, w)
+}
+
+/// Texture coordinates at `barycentric` inside the triangle `tri`
+fn hit_uv(mesh: &Mesh, tri: [usize; 3], barycentric: Vec3) -> Option<Vec2> {
+    let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) else {
+        return None;
+    };
+    let uv = |idx: usize| Vec2::from(uvs[idx]);
+    Some(uv(tri[0]) * barycentric.x + uv(tri[1]) * barycentric.y + uv(tri[2]) * barycentric.z)
+}
+
+/// Calls `f` with the model space vertices of every triangle in `mesh`, see `mesh_triangle_iter`
+fn for_each_model_triangle(mesh: &Mesh, f: impl FnMut([Vec3; 3])) {
+    if let Some(triangles) = mesh_triangle_iter(mesh) {
+        triangles.for_each(f);
+    }
+}
+
+/// Model space vertices of every triangle in `mesh`
+///
+/// Handles `TriangleList` and `TriangleStrip` topologies (wgpu has no triangle fans), strips
+/// can use primitive restart (an index of `u16::MAX`/`u32::MAX`)
+/// None for meshes without readable positions, without indices, or with a line/point topology,
+/// which have no triangles to hit
+pub fn mesh_triangle_iter(mesh: &Mesh) -> Option<impl Iterator<Item = [Vec3; 3]> + '_> {
+    let vertex_positions = vertex_positions(mesh)?;
+    let triangles = mesh_triangle_indices(mesh)?;
+
+    let vertex = move |idx: usize| Vec3::from(vertex_positions[idx]);
+    Some(triangles.map(move |[i0, i1, i2]| [vertex(i0), vertex(i1), vertex(i2)]))
+}
+
+/// Vertex indices of every triangle in `mesh`, see `mesh_triangle_iter`
+pub(crate) fn mesh_triangle_indices(
+    mesh: &Mesh,
+) -> Option<Box<dyn Iterator<Item = [usize; 3]> + '_>> {
+    let indices = mesh.indices()?;
+
+    match mesh.primitive_topology() {
+        PrimitiveTopology::TriangleList => {
+            let mut indices = indices.iter();
+            Some(Box::new(std::iter::from_fn(move || {
+                Some([indices.next()?, indices.next()?, indices.next()?])
+            })))
+        }
+        PrimitiveTopology::TriangleStrip => {
+            let restart = match indices {
+                Indices::U16(_) => u16::MAX as usize,
+                Indices::U32(_) => u32::MAX as usize,
+            };
+            let mut indices = indices.iter();
+            // last two indices of the strip, and whether the next triangle's winding is flipped
+            let mut window: [Option<usize>; 2] = [None, None];
+            let mut odd = false;
+            Some(Box::new(std::iter::from_fn(move || loop {
+                let c = indices.next()?;
+                if c == restart {
+                    window = [None, None];
+                    odd = false;
+                    continue;
+                }
+                let triangle = match window {
+                    // every other triangle in a strip is wound the other way round,
+                    // swap two vertices so they all face the same side
+                    [Some(a), Some(b)] if odd => Some([b, a, c]),
+                    [Some(a), Some(b)] => Some([a, b, c]),
+                    _ => None,
+                };
+                window = [window[1], Some(c)];
+                if triangle.is_some() {
+                    odd = !odd;
+                    return triangle;
+                }
+            })))
+        }
+        // lines and points, see `unpickable_reason`
+        _ => None,
+    }
+}
+
+/// Calls `f` with the world space vertices of every triangle in `mesh`
+fn for_each_triangle(mesh: &Mesh, transform: &GlobalTransform, mut f: impl FnMut([Vec3; 3])) {
+    // Transform the vertices from model space to world space
+    let mat = transform.compute_matrix();
+    for_each_model_triangle(mesh, |[v0, v1, v2]| {
+        f([
+            mat.transform_point3(v0),
+            mat.transform_point3(v1),
+            mat.transform_point3(v2),
+        ])
+    });
+}
+
+/// true if `point` (in world space) is inside `mesh`
+///
+/// Casts a ray from `point` and counts how many triangles it crosses: an odd count means the
+/// point is inside. This only gives meaningful answers for closed (watertight) meshes, a mesh
+/// with holes or open edges can report either answer for points near the gap.
+/// The ray direction is deliberately skewed off the axes, so it's unlikely to graze an edge or
+/// vertex shared between triangles (which would count one crossing twice).
+pub fn point_in_mesh(point: Vec3, mesh: &Mesh, transform: &GlobalTransform) -> bool {
+    let direction = Vec3::new(0.5773, 0.5774, 0.5775).normalize();
+    let mut crossings = 0;
+    for_each_triangle(mesh, transform, |[v0, v1, v2]| {
+        if moller_trumbore(point, direction, v0, v1, v2, DEFAULT_EPSILON, 0.0).is_some() {
+            crossings += 1;
+        }
+    });
+    crossings % 2 == 1
+}
+
+/// Nearest point on the surface of `mesh` to `from` (both in world space), and its distance
+/// None if the mesh has no triangles we can read
+pub fn closest_surface_point(
+    from: Vec3,
+    mesh: &Mesh,
+    transform: &GlobalTransform,
+) -> Option<(Vec3, f32)> {
+    let mut closest: Option<(Vec3, f32)> = None;
+    for_each_triangle(mesh, transform, |[v0, v1, v2]| {
+        let point = closest_point_on_triangle(from, v0, v1, v2);
+        let dist = point.distance(from);
+        match closest {
+            Some((_, d)) if d <= dist => (),
+            _ => closest = Some((point, dist)),
+        }
+    });
+    closest
+}
+
+/// Nearest point to `p` on the triangle (v0, v1, v2)
+/// see "Real-Time Collision Detection" (Ericson), 5.1.5
+/// works out which voronoi region of the triangle `p` projects into: one of the 3 vertices, one of
+/// the 3 edges, or the face itself
+fn closest_point_on_triangle(p: Vec3, v0: Vec3, v1: Vec3, v2: Vec3) -> Vec3 {
+    let ab = v1 - v0;
+    let ac = v2 - v0;
+
+    let ap = p - v0;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return v0;
+    }
+
+    let bp = p - v1;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return v1;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return v0 + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - v2;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return v2;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return v0 + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return v1 + (v2 - v1) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    // inside the face, project onto the triangle's plane
+    let denom = va + vb + vc;
+    if denom.abs() < f32::EPSILON {
+        // degenerate triangle, every region test above failed, any vertex will do
+        return v0;
+    }
+    let v = vb / denom;
+    let w = vc / denom;
+    v0 + ab * v + ac * w
+}
+
+/// Vertex positions of `mesh` as `[x, y, z]`
+/// `Float32x3` is borrowed as-is, the 4 component formats are converted by dropping the w
+/// component: `Float32x4`, and the quantized ones of compressed assets (`KHR_mesh_quantization`),
+/// normalized (`Snorm`/`Unorm`, decoded to [-1, 1] or [0, 1]) or not (`Sint`/`Uint`)
+/// bevy has no half float vertex formats, `Float16` positions have to be converted on load
+/// None if the mesh has no position attribute, or stores it in a format we can't read
+fn vertex_positions(mesh: &Mesh) -> Option<Cow<'_, [[f32; 3]]>> {
+    fn convert<T: Copy>(positions: &[[T; 4]], f: impl Fn(T) -> f32) -> Cow<'_, [[f32; 3]]> {
+        Cow::Owned(
+            positions
+                .iter()
+                .map(|[x, y, z, _w]| [f(*x), f(*y), f(*z)])
+                .collect(),
+        )
+    }
+    // the same way the GPU decodes them
+    let snorm16 = |c: i16| (c as f32 / i16::MAX as f32).max(-1.0);
+    let unorm16 = |c: u16| c as f32 / u16::MAX as f32;
+    let snorm8 = |c: i8| (c as f32 / i8::MAX as f32).max(-1.0);
+    let unorm8 = |c: u8| c as f32 / u8::MAX as f32;
+
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION)? {
+        VertexAttributeValues::Float32x3(positions) => Some(Cow::Borrowed(positions)),
+        VertexAttributeValues::Float32x4(positions) => Some(convert(positions, |c| c)),
+        VertexAttributeValues::Snorm16x4(positions) => Some(convert(positions, snorm16)),
+        VertexAttributeValues::Unorm16x4(positions) => Some(convert(positions, unorm16)),
+        VertexAttributeValues::Sint16x4(positions) => Some(convert(positions, f32::from)),
+        VertexAttributeValues::Uint16x4(positions) => Some(convert(positions, f32::from)),
+        VertexAttributeValues::Snorm8x4(positions) => Some(convert(positions, snorm8)),
+        VertexAttributeValues::Unorm8x4(positions) => Some(convert(positions, unorm8)),
+        VertexAttributeValues::Sint8x4(positions) => Some(convert(positions, f32::from)),
+        VertexAttributeValues::Uint8x4(positions) => Some(convert(positions, f32::from)),
+        _ => None,
+    }
+}
+
+/// Why `mesh` can't be picked, None if it can
+/// Reported once per mesh, see `bounds::update_mesh_bounds`
+fn unpickable_reason(mesh: &Mesh) -> Option<String> {
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION);
+    match positions {
+        None => return Some("it has no vertex positions".to_string()),
+        Some(positions) if vertex_positions(mesh).is_none() => {
+            return Some(format!(
+                "its vertex position format {:?} is unsupported",
+                VertexFormat::from(positions)
+            ))
+        }
+        Some(_) => (),
+    }
+    match mesh.primitive_topology() {
+        PrimitiveTopology::TriangleList | PrimitiveTopology::TriangleStrip
+            if mesh.indices().is_none() =>
+        {
+            Some("it has no indices".to_string())
+        }
+        PrimitiveTopology::TriangleList
+        | PrimitiveTopology::TriangleStrip
+        | PrimitiveTopology::LineList
+        | PrimitiveTopology::LineStrip => None,
+        topology => Some(format!(
+            "{topology:?} meshes have no triangles or lines to hit"
+        )),
     }
 }
 
 /// implements https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+/// rays within `epsilon` of parallel to the triangle (and degenerate triangles) never hit
+/// hits at a distance of `near_epsilon` or less from `ray_origin` are rejected
 pub fn moller_trumbore(
     ray_origin: Vec3,
     ray_direction: Vec3,
     v0: Vec3,
     v1: Vec3,
     v2: Vec3,
+    epsilon: f32,
+    near_epsilon: f32,
 ) -> Option<f32> {
-    let epsilon = 0.000_001;
     let edge1 = v1 - v0;
     let edge2 = v2 - v0;
+
+    // degenerate triangle with two coinciding vertices, rejected before the cross products
+    // collinear ones get through, the parallel test below rejects those (`a` is zero for them)
+    if is_degenerate(edge1, edge2, epsilon) {
+        return None;
+    }
+
     let h = ray_direction.cross(edge2);
     let a = edge1.dot(h);
 
@@ -314,25 +2725,197 @@ pub fn moller_trumbore(
 
     let t = f * edge2.dot(q);
 
-    if t > epsilon {
+    if t > near_epsilon {
         Some(t)
     } else {
         None
     }
 }
 
+/// true if any edge of the triangle spanned by `edge1` and `edge2` is shorter than `epsilon`
+/// cheaper than the full intersection test, so it's checked first
+fn is_degenerate(edge1: Vec3, edge2: Vec3, epsilon: f32) -> bool {
+    let min_length_squared = epsilon * epsilon;
+    edge1.length_squared() < min_length_squared
+        || edge2.length_squared() < min_length_squared
+        || (edge2 - edge1).length_squared() < min_length_squared
+}
+
+/// Distance along the ray, in multiples of `ray_direction`, where it crosses the infinite plane
+/// through `plane_point` with normal `plane_normal`
+/// Planes are two-sided; None if the ray is parallel to the plane or points away from it
+pub fn pick_plane(
+    ray_origin: Vec3,
+    ray_direction: Vec3,
+    plane_point: Vec3,
+    plane_normal: Vec3,
+) -> Option<f32> {
+    let denom = plane_normal.dot(ray_direction);
+    if denom == 0.0 {
+        return None;
+    }
+    let t = plane_normal.dot(plane_point - ray_origin) / denom;
+    (t >= 0.0).then_some(t)
+}
+
+/// Adds hover picking, with the picking systems in `PostUpdate`
+/// See `MouseRayPlugin::in_schedule` to run them elsewhere
+#[derive(Debug, Default, Clone, Copy)]
 pub struct MouseRayPlugin;
 
+impl MouseRayPlugin {
+    /// Runs the picking systems (`PickingSystems`) in `schedule` instead of `PostUpdate`
+    /// Add the returned plugin in place of `MouseRayPlugin`, not along with it
+    pub fn in_schedule(schedule: PickSchedule) -> ScheduledMouseRayPlugin {
+        ScheduledMouseRayPlugin { schedule }
+    }
+}
+
+/// `MouseRayPlugin` with the picking systems in a chosen schedule, see
+/// `MouseRayPlugin::in_schedule`
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduledMouseRayPlugin {
+    schedule: PickSchedule,
+}
+
+/// Schedule the picking systems run in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PickSchedule {
+    /// before game logic, so `Update` systems read this frame's hover
+    /// picks against last frame's `GlobalTransform`s, the latest ones before `PostUpdate`
+    /// propagates this frame's changes
+    PreUpdate,
+    /// after transform propagation, against this frame's `GlobalTransform`s, including moves of
+    /// both an entity and its parents within the frame
+    /// the hover is one frame behind for game logic in `Update`
+    #[default]
+    PostUpdate,
+    /// at the fixed timestep, for deterministic simulations
+    /// like `PreUpdate`, picks against the last propagated `GlobalTransform`s; cursor movements
+    /// between fixed steps are coalesced
+    FixedUpdate,
+}
+
+/// The systems that cast the pick ray and update hover state, for ordering your own around
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PickingSystems;
+
+fn picking_systems() -> SystemConfigs {
+    (
+        update_mouse_ray,
+        bounds::update_mesh_bounds,
+        (update_hover_state, update_cursor_over_bounds),
+        (
+            sync_current_hover,
+            send_hover_changed,
+            update_forward_sources,
+        ),
+    )
+        .chain()
+        .in_set(PickingSystems)
+}
+
 impl Plugin for MouseRayPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<HoverStart>()
+        MouseRayPlugin::in_schedule(PickSchedule::default()).build(app);
+    }
+
+    #[cfg(any(feature = "gpu_picking", feature = "depth_picking"))]
+    fn finish(&self, app: &mut App) {
+        MouseRayPlugin::in_schedule(PickSchedule::default()).finish(app);
+    }
+}
+
+impl Plugin for ScheduledMouseRayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PickingSettings>()
+            .init_resource::<VirtualCursor>()
+            .init_resource::<PickRay>()
+            .init_resource::<PickRayOverride>()
+            .init_resource::<IgnoredEntities>()
+            .init_resource::<CursorOverBounds>()
+            .init_resource::<PickingStats>()
+            .init_resource::<PointerCapture>()
+            .init_resource::<RayPick>()
+            .init_resource::<ReadbackPick>()
+            .init_resource::<Focused>()
+            .init_resource::<MeshCache>()
+            .add_event::<HoverStart>()
             .add_event::<HoverEnd>()
+            .add_event::<HoverChanged>()
+            .add_event::<HoverMove>()
             .add_event::<HoverPress>()
             .add_event::<HoverRelease>()
+            .add_event::<HoverClick>()
+            .add_event::<HoverPressCancel>()
+            .add_event::<DragStart>()
+            .add_event::<Drag>()
+            .add_event::<DragEnd>()
+            .add_event::<FocusChanged>()
             .add_systems(Startup, add_mouse_ray)
             .add_systems(Startup, add_resources)
-            .add_systems(Update, update_mouse_ray)
-            .add_systems(Update, update_hover_state)
-            .add_systems(Update, update_hover_press);
+            // bevy computes `Aabb`s for visible meshes, only fill in the ones it didn't
+            .add_systems(
+                PostUpdate,
+                bounds::insert_missing_aabbs.after(VisibilitySystems::CalculateBounds),
+            )
+            .add_systems(
+                Update,
+                (
+                    (update_hover_press, update_drag).chain(),
+                    focus::update_focus,
+                    outline::update_outlines,
+                    load_collider_sources,
+                ),
+            )
+            // in `Last`, so a fade turns around on the frame `Hover` changes in, whichever
+            // schedule picking runs in
+            .add_systems(Last, highlight::update_highlights);
+
+        match self.schedule {
+            // picking reads the camera's and the hoverables' `GlobalTransform`s, which are only
+            // brought up to date with this frame's `Transform` changes (including those of any
+            // parents, like a camera rig) by transform propagation
+            PickSchedule::PostUpdate => app.add_systems(
+                PostUpdate,
+                picking_systems().after(TransformSystem::TransformPropagate),
+            ),
+            // this frame's cursor events are only in after input handling
+            PickSchedule::PreUpdate => {
+                app.add_systems(PreUpdate, picking_systems().after(InputSystem))
+            }
+            PickSchedule::FixedUpdate => app.add_systems(FixedUpdate, picking_systems()),
+        };
+
+        // in `Last`, so the frame's `HoverChanged` is in whichever schedule picking runs in
+        #[cfg(feature = "channel")]
+        app.init_resource::<HoverChangedChannel>()
+            .add_systems(Last, channel::forward_hover_changed);
+
+        #[cfg(any(feature = "gpu_picking", feature = "depth_picking"))]
+        readback::build(app);
+        #[cfg(feature = "gpu_picking")]
+        gpu_pick::build(app);
+        #[cfg(feature = "depth_picking")]
+        depth_pick::build(app);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            Update,
+            (
+                async_raycast::spawn_async_raycasts,
+                async_raycast::poll_async_raycasts,
+            ),
+        );
+    }
+
+    // the GPU backends' pipelines need the `RenderDevice`, which is only there once the renderer
+    // has started
+    #[cfg(any(feature = "gpu_picking", feature = "depth_picking"))]
+    fn finish(&self, app: &mut App) {
+        #[cfg(feature = "gpu_picking")]
+        gpu_pick::finish(app);
+        #[cfg(feature = "depth_picking")]
+        depth_pick::finish(app);
     }
 }
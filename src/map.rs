@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+/// Maps a number from one range onto another, e.g. elapsed hover time onto a color intensity
+pub trait MapRange {
+    type Num;
+    fn map(&self, src: (Self::Num, Self::Num), dst: (Self::Num, Self::Num)) -> Self::Num;
+    fn map_clamped(&self, src: (Self::Num, Self::Num), dst: (Self::Num, Self::Num)) -> Self::Num;
+}
+
+impl MapRange for f32 {
+    type Num = f32;
+    fn map(&self, src: (f32, f32), dst: (f32, f32)) -> f32 {
+        // where self sits in src, then the same spot in dst
+        // inverse_lerp returns 0 for an empty src, so this maps to dst.0 instead of dividing by 0
+        lerp(dst.0, dst.1, inverse_lerp(src.0, src.1, *self))
+    }
+    fn map_clamped(&self, src: (f32, f32), dst: (f32, f32)) -> f32 {
+        let clamped = if src.0 <= src.1 {
+            self.clamp(src.0, src.1)
+        } else {
+            self.clamp(src.1, src.0)
+        };
+
+        clamped.map(src, dst)
+    }
+}
+
+/// Types that can be linearly interpolated, see `lerp` and `inverse_lerp`
+pub trait Lerp: Copy {
+    fn lerp(a: Self, b: Self, t: f32) -> Self;
+    fn inverse_lerp(a: Self, b: Self, value: Self) -> f32;
+}
+
+impl Lerp for f32 {
+    fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        a + (b - a) * t
+    }
+    fn inverse_lerp(a: f32, b: f32, value: f32) -> f32 {
+        if a == b {
+            return 0.0; // avoid div by 0
+        }
+        (value - a) / (b - a)
+    }
+}
+
+macro_rules! impl_lerp_for_vec {
+    ($($t:ty),*) => {$(
+        impl Lerp for $t {
+            fn lerp(a: $t, b: $t, t: f32) -> $t {
+                a + (b - a) * t
+            }
+            /// `value` is projected onto the line through a and b first
+            fn inverse_lerp(a: $t, b: $t, value: $t) -> f32 {
+                let ab = b - a;
+                let len_sq = ab.length_squared();
+                if len_sq == 0.0 {
+                    return 0.0; // avoid div by 0
+                }
+                (value - a).dot(ab) / len_sq
+            }
+        }
+    )*};
+}
+
+impl_lerp_for_vec!(Vec2, Vec3, Vec4);
+
+/// Value `t` of the way from `a` to `b`
+/// t = 0 gives a, t = 1 gives b, t outside [0, 1] extrapolates past them
+pub fn lerp<T: Lerp>(a: T, b: T, t: f32) -> T {
+    T::lerp(a, b, t)
+}
+
+/// Inverse of `lerp`: how far `value` is along the way from `a` to `b`
+/// 0 at a, 1 at b, outside [0, 1] if value is outside the range
+/// 0 if a == b
+pub fn inverse_lerp<T: Lerp>(a: T, b: T, value: T) -> f32 {
+    T::inverse_lerp(a, b, value)
+}
@@ -0,0 +1,53 @@
+use bevy::gizmos::AabbGizmo;
+use bevy::prelude::*;
+
+use crate::Hover;
+
+/// Outlines whatever is hovered with a gizmo of its `Aabb`, a highlight that needs no materials
+/// Insert it as a resource to turn it on, remove it to turn it off again
+/// Drawn by bevy's `GizmoPlugin` (part of `DefaultPlugins`), nothing shows without it
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct HoverOutline {
+    /// None for the gizmo config's default aabb color
+    pub color: Option<Color>,
+}
+
+/// Marks an `AabbGizmo` inserted by `update_outlines`, so it's removed when the hover ends
+/// Entities with an `AabbGizmo` of their own are left alone
+#[derive(Component)]
+pub(crate) struct HoverOutlined;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_outlines(
+    mut commands: Commands,
+    outline: Option<Res<HoverOutline>>,
+    hovered: Query<Entity, (With<Hover>, Without<AabbGizmo>)>,
+    mut outlined: Query<(Entity, Has<Hover>, &mut AabbGizmo), With<HoverOutlined>>,
+) {
+    for (entity, hovered, mut gizmo) in outlined.iter_mut() {
+        match outline.as_deref() {
+            Some(outline) if hovered => {
+                // picks up color changes
+                if gizmo.color != outline.color {
+                    gizmo.color = outline.color;
+                }
+            }
+            _ => {
+                commands
+                    .entity(entity)
+                    .remove::<(AabbGizmo, HoverOutlined)>();
+            }
+        }
+    }
+    let Some(outline) = outline else {
+        return;
+    };
+    for entity in hovered.iter() {
+        commands.entity(entity).insert((
+            AabbGizmo {
+                color: outline.color,
+            },
+            HoverOutlined,
+        ));
+    }
+}
@@ -0,0 +1,245 @@
+//! Plumbing for the GPU `PickingBackend`s: which pixel the render world reads, and getting what
+//! it read there back to the main world
+//! Buffers can only be mapped once the commands copying into them are submitted, so a value
+//! arrives a frame or two after it was requested
+
+use std::sync::{Arc, Mutex, Once};
+
+use bevy::core_pipeline::prepass::DepthPrepass;
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::primitives::Aabb;
+use bevy::render::render_resource::{Buffer, BufferDescriptor, BufferUsages, MapMode};
+use bevy::render::renderer::RenderDevice;
+use bevy::render::{Render, RenderApp, RenderSet};
+
+#[cfg(feature = "depth_picking")]
+use crate::depth_pick;
+#[cfg(feature = "gpu_picking")]
+use crate::gpu_pick;
+use crate::{
+    Hoverable, MouseRay, PickRayOverride, PickingBackend, PickingSettings, RaySource, ReadbackPick,
+};
+
+/// Size of a readback, two 32 bit words
+pub(crate) const READBACK_SIZE: u64 = 8;
+
+/// The pixel under the cursor, for the render world to read back
+/// None with `PickingBackend::Cpu`, without a cursor or camera, and while `PickRayOverride` is set
+/// and with `PickingBackend::Depth` until the camera has a `DepthPrepass`
+#[derive(Resource, Debug, Default, Clone, Copy, ExtractResource)]
+pub(crate) struct ReadbackRequest(pub(crate) Option<ReadbackCursor>);
+
+// each backend reads the fields it needs
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReadbackCursor {
+    pub(crate) backend: PickingBackend,
+    /// the `RaySource::Mouse` camera, the same entity in the render world
+    pub(crate) camera: Entity,
+    /// normalized device coordinates in the camera's viewport, like `VirtualCursor`
+    pub(crate) ndc: Vec2,
+    /// physical pixel under the cursor in the camera's render target
+    pub(crate) pixel: UVec2,
+    /// size of the camera's viewport in physical pixels
+    pub(crate) viewport_size: UVec2,
+    pub(crate) clip_from_world: Mat4,
+}
+
+/// Hoverables a readback can name, with their bounds
+pub(crate) type Hoverables<'w, 's> =
+    Query<'w, 's, (Entity, &'static Aabb, &'static GlobalTransform), With<Hoverable>>;
+
+/// Words read back for a request, shared by both worlds
+/// Only the latest is kept, older ones are out of date anyway
+#[derive(Resource, Default, Clone)]
+pub(crate) struct ReadbackSlot(Arc<Mutex<Option<Readback>>>);
+
+/// The words read back, with the request they were read for
+type Readback = (ReadbackCursor, [u32; 2]);
+
+/// Readbacks in flight at once, a buffer is only free again once it's been read
+const READBACK_RING: usize = 3;
+
+/// The readback buffers, reused frame to frame
+#[derive(Resource, Default)]
+pub(crate) struct PendingReadbacks {
+    /// copied into this frame, mapped once the frame's commands are submitted
+    copied: Mutex<Vec<(Buffer, ReadbackCursor)>>,
+    /// read and unmapped, ready to be copied into again
+    free: Arc<Mutex<Vec<Buffer>>>,
+    /// how many have been made, at most `READBACK_RING`
+    made: Mutex<usize>,
+}
+
+impl PendingReadbacks {
+    /// Buffer for a render graph node to copy `READBACK_SIZE` bytes read for `cursor` into
+    /// None while all of them are still being read, the last readback stands until one is free
+    pub(crate) fn buffer(
+        &self,
+        render_device: &RenderDevice,
+        cursor: ReadbackCursor,
+    ) -> Option<Buffer> {
+        let buffer = match self.free.lock().unwrap().pop() {
+            Some(buffer) => buffer,
+            None => {
+                let mut made = self.made.lock().unwrap();
+                if *made >= READBACK_RING {
+                    return None;
+                }
+                *made += 1;
+                render_device.create_buffer(&BufferDescriptor {
+                    label: Some("bevy_hover_readback"),
+                    size: READBACK_SIZE,
+                    usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                })
+            }
+        };
+        self.copied.lock().unwrap().push((buffer.clone(), cursor));
+        Some(buffer)
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    let slot = ReadbackSlot::default();
+    app.init_resource::<ReadbackRequest>()
+        .insert_resource(slot.clone())
+        .add_plugins(ExtractResourcePlugin::<ReadbackRequest>::default())
+        // before picking, in whichever schedule it runs
+        .add_systems(First, apply_readback)
+        // after the cursor and the camera have moved this frame
+        .add_systems(Last, update_readback_request);
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .insert_resource(slot)
+        .init_resource::<PendingReadbacks>()
+        // the frame's commands are submitted during `RenderSet::Render`
+        .add_systems(Render, map_readbacks.in_set(RenderSet::Cleanup));
+}
+
+impl PickingBackend {
+    /// true for backends reading the camera's `DepthPrepass`
+    fn needs_depth_prepass(self) -> bool {
+        #[cfg(feature = "depth_picking")]
+        if self == PickingBackend::Depth {
+            return true;
+        }
+        false
+    }
+}
+
+fn update_readback_request(
+    settings: Res<PickingSettings>,
+    ray_override: Res<PickRayOverride>,
+    mouse_rays: Query<&MouseRay>,
+    cameras: Query<(
+        Entity,
+        &Camera,
+        &GlobalTransform,
+        &RaySource,
+        Has<DepthPrepass>,
+    )>,
+    mut request: ResMut<ReadbackRequest>,
+) {
+    request.0 = None;
+    if settings.backend == PickingBackend::Cpu || ray_override.0.is_some() {
+        return;
+    }
+    let Some(ndc) = mouse_rays
+        .get_single()
+        .ok()
+        .filter(|ray| !ray.detached)
+        .and_then(|ray| ray.cursor)
+    else {
+        return;
+    };
+    let Some((entity, camera, transform, _, depth_prepass)) = cameras
+        .iter()
+        .find(|(_, _, _, source, _)| **source == RaySource::Mouse)
+    else {
+        return;
+    };
+    if settings.backend.needs_depth_prepass() && !depth_prepass {
+        static WARNED: Once = Once::new();
+        WARNED.call_once(|| {
+            warn!(
+                "bevy-hover: {:?} needs a DepthPrepass on the RaySource::Mouse camera, \
+                 picking stays on the CPU until it has one",
+                settings.backend
+            )
+        });
+        return;
+    }
+    let Some(viewport) = camera.physical_viewport_rect() else {
+        return;
+    };
+    let viewport_size = viewport.size();
+    let offset = ((ndc * Vec2::new(0.5, -0.5) + 0.5) * viewport_size.as_vec2()).as_uvec2();
+    request.0 = Some(ReadbackCursor {
+        backend: settings.backend,
+        camera: entity,
+        ndc,
+        pixel: viewport.min + offset.min(viewport_size.saturating_sub(UVec2::ONE)),
+        viewport_size,
+        clip_from_world: camera.projection_matrix() * transform.compute_matrix().inverse(),
+    });
+}
+
+fn map_readbacks(
+    render_device: Res<RenderDevice>,
+    pending: Res<PendingReadbacks>,
+    slot: Res<ReadbackSlot>,
+) {
+    for (buffer, cursor) in pending.copied.lock().unwrap().drain(..) {
+        let slot = slot.clone();
+        let free = pending.free.clone();
+        let mapped = buffer.clone();
+        render_device.map_buffer(&buffer.slice(..), MapMode::Read, move |result| {
+            if result.is_err() {
+                free.lock().unwrap().push(mapped);
+                return;
+            }
+            let words = {
+                let data = mapped.slice(..).get_mapped_range();
+                let word =
+                    |i: usize| u32::from_le_bytes(data[i * 4..i * 4 + 4].try_into().unwrap());
+                [word(0), word(1)]
+            };
+            mapped.unmap();
+            free.lock().unwrap().push(mapped);
+            *slot.0.lock().unwrap() = Some((cursor, words));
+        });
+    }
+}
+
+fn apply_readback(
+    settings: Res<PickingSettings>,
+    request: Res<ReadbackRequest>,
+    slot: Res<ReadbackSlot>,
+    hoverables: Hoverables,
+    mut pick: ResMut<ReadbackPick>,
+) {
+    if request.0.is_none() {
+        // back on the CPU
+        pick.0 = None;
+        return;
+    }
+    let Some((cursor, words)) = slot.0.lock().unwrap().take() else {
+        // nothing new, the last readback stands
+        return;
+    };
+    // read for a backend that has since been switched away from
+    if cursor.backend != settings.backend {
+        return;
+    }
+    pick.0 = match cursor.backend {
+        PickingBackend::Cpu => None,
+        #[cfg(feature = "gpu_picking")]
+        PickingBackend::Gpu => Some(gpu_pick::decode(words, &hoverables)),
+        #[cfg(feature = "depth_picking")]
+        PickingBackend::Depth => Some(depth_pick::decode(words, cursor, &hoverables)),
+    };
+}
@@ -0,0 +1,50 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+
+/// Reads texel colors from hoverables' `StandardMaterial::base_color_texture`
+#[derive(SystemParam)]
+pub(crate) struct TexelSampler<'w, 's> {
+    materials: Query<'w, 's, &'static Handle<StandardMaterial>>,
+    material_assets: Res<'w, Assets<StandardMaterial>>,
+    images: Res<'w, Assets<Image>>,
+}
+
+impl TexelSampler<'_, '_> {
+    /// Color of `entity`'s base color texture at `uv`
+    pub(crate) fn sample(&self, entity: Entity, uv: Vec2) -> Option<Color> {
+        let material = self.material_assets.get(self.materials.get(entity).ok()?)?;
+        let image = self.images.get(material.base_color_texture.as_ref()?)?;
+        sample_image(image, uv)
+    }
+}
+
+/// Nearest-neighbor sample of `image` at `uv`, repeating outside [0, 1]
+/// (0, 0) is the top left of the image
+/// None for formats other than 8 bit RGBA
+fn sample_image(image: &Image, uv: Vec2) -> Option<Color> {
+    let size = image.texture_descriptor.size;
+    let (width, height) = (size.width as usize, size.height as usize);
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let uv = uv - uv.floor();
+    let x = ((uv.x * width as f32) as usize).min(width - 1);
+    let y = ((uv.y * height as f32) as usize).min(height - 1);
+    let offset = (y * width + x) * 4;
+    let &[r, g, b, a] = image.data.get(offset..offset + 4)? else {
+        return None;
+    };
+
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb => Some(Color::rgba_u8(r, g, b, a)),
+        TextureFormat::Rgba8Unorm => Some(Color::rgba_linear(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        )),
+        _ => None,
+    }
+}
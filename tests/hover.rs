@@ -0,0 +1,3276 @@
+//! Headless runs of the whole picking pipeline: a quad, a camera rendering to an image (so no
+//! window is needed) and a `VirtualCursor` standing in for the mouse
+
+use std::sync::Arc;
+
+use bevy::core::FrameCount;
+use bevy::ecs::event::ManualEventReader;
+use bevy::gizmos::AabbGizmo;
+use bevy::prelude::*;
+use bevy::render::camera::{
+    camera_system, ManualTextureViews, RenderTarget, ScalingMode, Viewport,
+};
+use bevy::render::mesh::{Indices, MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::primitives::Aabb;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, VertexFormat};
+use bevy::time::TimeUpdateStrategy;
+use bevy::transform::TransformSystem;
+use bevy::window::{WindowCreated, WindowFocused, WindowResized};
+use bevy_hover::map::{inverse_lerp, lerp, MapRange};
+use bevy_hover::{
+    cast_ray, cast_ray_filtered, closest_surface_point, mesh_triangle_iter, moller_trumbore,
+    pick_under_cursor, point_in_mesh, screen_ndc_to_ray, AsyncRaycast, AsyncRaycastResult,
+    CurrentHover, CursorConvention, CursorOrigin, CursorOverBounds, Drag, DragEnd, DragPlane,
+    Focusable, Focused, Hover, HoverAppExt, HoverCapsule, HoverChanged, HoverClick,
+    HoverColliderSource, HoverConvexHull, HoverEnd, HoverHeightfield, HoverHighlight,
+    HoverIntersect, HoverIntersector, HoverLayer, HoverLod, HoverMode, HoverMove, HoverObb,
+    HoverOutline, HoverPlane, HoverPressCancel, HoverStart, Hoverable, Hovered, IgnoredEntities,
+    MouseRayPlugin, Occluder, PickMode, PickRay, PickRayOverride, PickSchedule, PickingSettings,
+    PickingStats, PointerCapture, RayHit, RaySource, VirtualCursor, DEFAULT_EPSILON,
+};
+
+/// App with a 2x2 quad at the origin, facing a camera 5 units away along +Z
+fn app(projection: Projection) -> (App, Entity) {
+    app_in(PickSchedule::default(), projection)
+}
+
+/// Like `app`, with picking in `schedule`
+fn app_in(schedule: PickSchedule, projection: Projection) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), TransformPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<Image>()
+        .init_asset::<StandardMaterial>()
+        .init_resource::<Input<MouseButton>>()
+        .init_resource::<Input<KeyCode>>()
+        .init_resource::<ManualTextureViews>()
+        .add_event::<CursorMoved>()
+        .add_event::<WindowFocused>()
+        .add_event::<WindowCreated>()
+        .add_event::<WindowResized>()
+        // normally part of the render plugins: computes the camera's projection matrix
+        .add_systems(
+            PostUpdate,
+            camera_system::<Projection>.before(TransformSystem::TransformPropagate),
+        )
+        .add_plugins(MouseRayPlugin::in_schedule(schedule));
+
+    let target = app
+        .world
+        .resource_mut::<Assets<Image>>()
+        .add(Image::new_fill(
+            Extent3d {
+                width: 100,
+                height: 100,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        ));
+    app.world.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(target),
+                ..default()
+            },
+            projection,
+            transform: Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        RaySource::Mouse,
+    ));
+
+    let quad = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Mesh::from(shape::Quad::new(Vec2::splat(2.0))));
+    let entity = app
+        .world
+        .spawn((quad, SpatialBundle::default(), Hoverable))
+        .id();
+    (app, entity)
+}
+
+fn run(app: &mut App, cursor: Vec2) {
+    app.world.resource_mut::<VirtualCursor>().0 = Some(cursor);
+    for _ in 0..3 {
+        app.update();
+    }
+}
+
+fn drain<'a, E: Event>(app: &'a App, reader: &'a mut ManualEventReader<E>) -> Vec<&'a E> {
+    reader.read(app.world.resource::<Events<E>>()).collect()
+}
+
+/// Hovers the quad through the center of the view, then moves the cursor off it
+fn assert_hover_sequence(projection: Projection) {
+    let (mut app, quad) = app(projection);
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+
+    run(&mut app, Vec2::ZERO);
+    let started = drain(&app, &mut starts);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].hovered, quad);
+    assert!(drain(&app, &mut ends).is_empty());
+    let point = app.world.get::<Hover>(quad).unwrap().hit.point;
+    assert!(point.abs_diff_eq(Vec3::ZERO, 1e-4), "hit {point}");
+
+    // well outside the quad for both cameras
+    run(&mut app, Vec2::splat(0.9));
+    assert!(drain(&app, &mut starts).is_empty());
+    let ended = drain(&app, &mut ends);
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].hovered, quad);
+    assert!(ended[0].last_point.abs_diff_eq(Vec3::ZERO, 1e-4));
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn perspective_hover() {
+    assert_hover_sequence(Projection::Perspective(default()));
+}
+
+#[test]
+fn orthographic_hover() {
+    assert_hover_sequence(Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::Fixed {
+            width: 4.0,
+            height: 4.0,
+        },
+        ..default()
+    }));
+}
+
+#[test]
+fn forward_source_hover() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // off to the side of the camera, aimed at the quad's corner
+    let source = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(
+                Transform::from_xyz(3.0, 0.0, 3.0).looking_at(Vec3::new(0.9, 0.0, 0.0), Vec3::Y),
+            ),
+            RaySource::Forward,
+        ))
+        .id();
+
+    // the cursor is off the quad, only the forward source hovers it
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(
+        app.world.get::<CurrentHover>(source),
+        Some(&CurrentHover(Some(quad)))
+    );
+    assert!(app.world.get::<Hover>(quad).is_none());
+
+    app.world
+        .entity_mut(source)
+        .insert(Transform::from_xyz(3.0, 0.0, 3.0).looking_at(Vec3::new(3.0, 0.0, 0.0), Vec3::Y));
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(
+        app.world.get::<CurrentHover>(source),
+        Some(&CurrentHover(None))
+    );
+}
+
+#[test]
+fn over_bounds_without_hover() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    // lower left half of the quad, its bounds still cover the upper right half
+    let mut triangle = Mesh::new(PrimitiveTopology::TriangleList);
+    triangle.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, 1.0, 0.0]],
+    );
+    triangle.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+    let triangle = app.world.resource_mut::<Assets<Mesh>>().add(triangle);
+    let entity = app
+        .world
+        .spawn((triangle, SpatialBundle::default(), Hoverable))
+        .id();
+
+    run(&mut app, Vec2::splat(0.2));
+    assert_eq!(
+        *app.world.resource::<CursorOverBounds>(),
+        CursorOverBounds(true)
+    );
+    assert!(app.world.get::<Hover>(entity).is_none());
+
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(
+        *app.world.resource::<CursorOverBounds>(),
+        CursorOverBounds(false)
+    );
+}
+
+#[test]
+fn orthographic_full_line() {
+    let (mut app, quad) = app(Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::Fixed {
+            width: 4.0,
+            height: 4.0,
+        },
+        ..default()
+    }));
+    // on the camera's side of the image plane, 1 unit behind it
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let behind = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 6.0)),
+            Hoverable,
+        ))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+    assert!(app.world.get::<Hover>(behind).is_none());
+
+    app.insert_resource(PickingSettings {
+        ortho_full_line: true,
+        ..default()
+    });
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+    let hit = app.world.get::<Hover>(behind).unwrap().hit;
+    assert!(
+        (hit.distance + 1.0).abs() < 1e-3,
+        "distance {}",
+        hit.distance
+    );
+    assert!(hit.point.abs_diff_eq(Vec3::new(0.0, 0.0, 6.0), 1e-3));
+}
+
+#[test]
+fn picking_stats() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // well out of the ray's way, only its bounds are tested
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    app.world.spawn((
+        mesh,
+        SpatialBundle::from_transform(Transform::from_xyz(50.0, 0.0, 0.0)),
+        Hoverable,
+    ));
+
+    run(&mut app, Vec2::ZERO);
+    let stats = *app.world.resource::<PickingStats>();
+    assert_eq!(stats.entities_tested, 1, "{stats:?}");
+    assert_eq!(stats.triangles_tested, 2, "{stats:?}");
+    assert_eq!(stats.broad_phase_rejected, 1, "{stats:?}");
+}
+
+/// Collects the names of all spans created, on any thread
+#[cfg(feature = "trace")]
+struct SpanNames(std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+#[cfg(feature = "trace")]
+impl<S: bevy::utils::tracing::Subscriber> tracing_subscriber::Layer<S> for SpanNames {
+    fn on_new_span(
+        &self,
+        attrs: &bevy::utils::tracing::span::Attributes<'_>,
+        _id: &bevy::utils::tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        self.0.lock().unwrap().push(attrs.metadata().name());
+    }
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn trace_spans() {
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    let names = std::sync::Arc::default();
+    // global, systems may run on any thread
+    bevy::utils::tracing::subscriber::set_global_default(
+        Registry::default().with(SpanNames(std::sync::Arc::clone(&names))),
+    )
+    .unwrap();
+
+    let (mut app, _) = app(Projection::Perspective(default()));
+    run(&mut app, Vec2::ZERO);
+    let names = names.lock().unwrap();
+    for expected in ["update_mouse_ray", "update_hover_state", "check_intersect"] {
+        assert!(names.contains(&expected), "no {expected} span");
+    }
+}
+
+#[test]
+fn xr_controller_source() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // tracking space moved away from the world origin, the way XR rigs usually are, with the
+    // controller posed inside it and aimed back down at the quad
+    let controller = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(
+                Transform::from_xyz(0.0, 1.0, 0.0).looking_at(Vec3::new(0.0, -1.0, -3.0), Vec3::Y),
+            ),
+            RaySource::Forward,
+        ))
+        .id();
+    app.world
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            0.0, 1.0, 3.0,
+        )))
+        .add_child(controller);
+
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(
+        app.world.get::<CurrentHover>(controller),
+        Some(&CurrentHover(Some(quad)))
+    );
+
+    // pointing up, over the quad
+    app.world
+        .entity_mut(controller)
+        .insert(Transform::from_xyz(0.0, 1.0, 0.0).looking_at(Vec3::new(0.0, 2.0, -3.0), Vec3::Y));
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(
+        app.world.get::<CurrentHover>(controller),
+        Some(&CurrentHover(None))
+    );
+}
+
+#[test]
+fn keyboard_activation() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut clicks = ManualEventReader::<HoverClick>::default();
+    run(&mut app, Vec2::ZERO);
+
+    app.world
+        .resource_mut::<Input<KeyCode>>()
+        .press(KeyCode::Return);
+    app.update();
+    assert!(drain(&app, &mut clicks).is_empty());
+
+    let mut keys = app.world.resource_mut::<Input<KeyCode>>();
+    keys.clear();
+    keys.release(KeyCode::Return);
+    app.update();
+    let clicked = drain(&app, &mut clicks);
+    assert_eq!(clicked.len(), 1);
+    assert_eq!(clicked[0].entity, quad);
+    assert_eq!(clicked[0].button, MouseButton::Left);
+}
+
+#[test]
+fn material_culling() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // turned away from the camera, which now sees its back
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_rotation(Quat::from_rotation_y(
+            std::f32::consts::PI,
+        )));
+    let mut materials = app.world.resource_mut::<Assets<StandardMaterial>>();
+    // back faces are culled by default
+    let front_only = materials.add(StandardMaterial::default());
+    let double_sided = materials.add(StandardMaterial {
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    });
+
+    app.world.entity_mut(quad).insert(front_only);
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+
+    app.world.entity_mut(quad).insert(double_sided);
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+}
+
+#[test]
+fn hover_after_propagation() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let parent = app.world.spawn(SpatialBundle::default()).id();
+    app.world.entity_mut(parent).add_child(quad);
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+
+    // both move within the same frame, the hit has to account for both
+    app.world
+        .entity_mut(parent)
+        .insert(Transform::from_xyz(0.0, 0.0, 1.0));
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_xyz(0.0, 0.0, 0.5));
+    app.update();
+    let point = app.world.get::<Hover>(quad).unwrap().hit.point;
+    assert!(
+        point.abs_diff_eq(Vec3::new(0.0, 0.0, 1.5), 1e-4),
+        "hit {point}"
+    );
+
+    // moved out from under the cursor by its parent alone
+    app.world
+        .entity_mut(parent)
+        .insert(Transform::from_xyz(3.0, 0.0, 1.0));
+    app.update();
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn mesh_edits_are_picked_up() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+
+    // shrink the quad into a corner, away from the cursor
+    let handle = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let mut meshes = app.world.resource_mut::<Assets<Mesh>>();
+    let mesh = meshes.get_mut(&handle).unwrap();
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("quad positions aren't Float32x3");
+    };
+    for position in positions.iter_mut() {
+        position[0] = position[0] * 0.25 + 0.75;
+        position[1] = position[1] * 0.25 + 0.75;
+    }
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+
+    // and under the cursor again, at a different depth
+    let mut meshes = app.world.resource_mut::<Assets<Mesh>>();
+    let mesh = meshes.get_mut(&handle).unwrap();
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("quad positions aren't Float32x3");
+    };
+    for position in positions.iter_mut() {
+        position[0] = (position[0] - 0.75) * 4.0;
+        position[1] = (position[1] - 0.75) * 4.0;
+        position[2] = 1.0;
+    }
+    run(&mut app, Vec2::ZERO);
+    let point = app.world.get::<Hover>(quad).unwrap().hit.point;
+    assert!(
+        point.abs_diff_eq(Vec3::new(0.0, 0.0, 1.0), 1e-4),
+        "hit {point}"
+    );
+}
+
+#[test]
+fn pointer_capture() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+    let mut drags = ManualEventReader::<Drag>::default();
+    let mut drag_ends = ManualEventReader::<DragEnd>::default();
+    run(&mut app, Vec2::ZERO);
+
+    app.world
+        .resource_mut::<Input<MouseButton>>()
+        .press(MouseButton::Left);
+    app.update();
+    app.world.resource_mut::<Input<MouseButton>>().clear();
+    assert_eq!(app.world.resource::<PointerCapture>().0, Some(quad));
+
+    // dragged well off the quad, it keeps the hover
+    run(&mut app, Vec2::splat(0.9));
+    run(&mut app, Vec2::new(0.9, -0.9));
+    assert!(drain(&app, &mut ends).is_empty());
+    assert!(app.world.get::<Hover>(quad).is_some());
+    let dragged = drain(&app, &mut drags);
+    assert!(!dragged.is_empty());
+    assert!(dragged.iter().all(|drag| drag.entity == quad));
+
+    app.world
+        .resource_mut::<Input<MouseButton>>()
+        .release(MouseButton::Left);
+    app.update();
+    app.world.resource_mut::<Input<MouseButton>>().clear();
+    app.update();
+    assert_eq!(app.world.resource::<PointerCapture>().0, None);
+    assert_eq!(drain(&app, &mut drag_ends).len(), 1);
+    let ended = drain(&app, &mut ends);
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].hovered, quad);
+}
+
+#[test]
+fn press_cancelled_off_entity() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut clicks = ManualEventReader::<HoverClick>::default();
+    let mut cancels = ManualEventReader::<HoverPressCancel>::default();
+    let press = |app: &mut App, release: bool| {
+        let mut input = app.world.resource_mut::<Input<MouseButton>>();
+        if release {
+            input.release(MouseButton::Left);
+        } else {
+            input.press(MouseButton::Left);
+        }
+        app.update();
+        app.world.resource_mut::<Input<MouseButton>>().clear();
+        app.update();
+    };
+
+    // released over the quad, a click
+    run(&mut app, Vec2::ZERO);
+    press(&mut app, false);
+    press(&mut app, true);
+    let clicked = drain(&app, &mut clicks);
+    assert_eq!(clicked.len(), 1);
+    assert_eq!(clicked[0].entity, quad);
+    assert!(drain(&app, &mut cancels).is_empty());
+
+    // moved off it before the release, a cancel, even though the capture kept the hover
+    press(&mut app, false);
+    run(&mut app, Vec2::splat(0.9));
+    assert!(app.world.get::<Hover>(quad).is_some());
+    press(&mut app, true);
+    assert!(drain(&app, &mut clicks).is_empty());
+    let cancelled = drain(&app, &mut cancels);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].entity, quad);
+    assert_eq!(cancelled[0].button, MouseButton::Left);
+}
+
+#[test]
+fn hover_highlight() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        std::time::Duration::from_millis(100),
+    ));
+    let material = app
+        .world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    app.world.entity_mut(quad).insert((
+        material.clone(),
+        HoverHighlight {
+            emissive: Color::WHITE,
+            fade: std::time::Duration::from_secs(1),
+        },
+    ));
+    let emissive = |app: &App| {
+        let handle = app.world.get::<Handle<StandardMaterial>>(quad).unwrap();
+        let material = app.world.resource::<Assets<StandardMaterial>>().get(handle);
+        material.unwrap().emissive.as_linear_rgba_f32()[0]
+    };
+
+    run(&mut app, Vec2::ZERO);
+    let ramping = emissive(&app);
+    run(&mut app, Vec2::ZERO);
+    let ramped = emissive(&app);
+    assert!(0.0 < ramping && ramping < ramped, "{ramping} {ramped}");
+    // lit up on a copy, the shared material is left alone
+    let shared = app.world.resource::<Assets<StandardMaterial>>();
+    assert_eq!(shared.get(&material).unwrap().emissive, Color::BLACK);
+
+    run(&mut app, Vec2::splat(0.9));
+    let fading = emissive(&app);
+    assert!(fading < ramped, "{fading} {ramped}");
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(emissive(&app), 0.0);
+}
+
+#[test]
+fn quantized_positions() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // the same 2x2 quad, as a compressed glTF would store it: normalized 16 bit components,
+    // scaled back up by the transform
+    let position = MeshVertexAttribute::new(
+        "Vertex_Position",
+        // the id of `Mesh::ATTRIBUTE_POSITION`, so it's read in place of it
+        0,
+        VertexFormat::Snorm16x4,
+    );
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        position,
+        VertexAttributeValues::Snorm16x4(vec![
+            [-i16::MAX, -i16::MAX, 0, 0],
+            [i16::MAX, -i16::MAX, 0, 0],
+            [i16::MAX, i16::MAX, 0, 0],
+            [-i16::MAX, i16::MAX, 0, 0],
+        ]),
+    );
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2, 0, 2, 3])));
+    let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+    app.world.entity_mut(quad).insert((
+        mesh,
+        Transform::from_xyz(0.25, 0.0, 0.0).with_scale(Vec3::splat(0.5)),
+    ));
+
+    run(&mut app, Vec2::ZERO);
+    let point = app.world.get::<Hover>(quad).unwrap().hit.point;
+    assert!(point.abs_diff_eq(Vec3::ZERO, 1e-4), "hit {point}");
+    // the quad now spans x -0.25 to 0.75, the cursor is left of it
+    run(&mut app, Vec2::new(-0.2, 0.0));
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[derive(Resource)]
+struct Picked(Option<RayHit>);
+
+#[test]
+fn pick_under_cursor_now() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.add_systems(Update, |world: &mut World| {
+        let hit = pick_under_cursor(world);
+        world.insert_resource(Picked(hit));
+    });
+
+    // the hover pass hasn't run yet when `Update` does on the first frame, the pick doesn't wait
+    app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::ZERO);
+    app.update();
+    app.update();
+    let hit = app.world.resource::<Picked>().0.unwrap();
+    assert_eq!(hit.entity, quad);
+    assert!(hit.point.abs_diff_eq(Vec3::ZERO, 1e-4), "hit {}", hit.point);
+
+    app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::splat(0.9));
+    app.update();
+    assert!(app.world.resource::<Picked>().0.is_none());
+}
+
+#[test]
+fn pick_under_window_cursor() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // without a `VirtualCursor`, read straight from the window
+    let mut window = Window {
+        resolution: (100.0, 100.0).into(),
+        ..default()
+    };
+    window.set_cursor_position(Some(Vec2::new(50.0, 50.0)));
+    let window = app.world.spawn(window).id();
+    app.update();
+    let hit = pick_under_cursor(&mut app.world).unwrap();
+    assert_eq!(hit.entity, quad);
+    assert!(hit.point.abs_diff_eq(Vec3::ZERO, 1e-4), "hit {}", hit.point);
+
+    // the top right corner, well off the quad
+    app.world
+        .get_mut::<Window>(window)
+        .unwrap()
+        .set_cursor_position(Some(Vec2::new(95.0, 5.0)));
+    assert!(pick_under_cursor(&mut app.world).is_none());
+    // and no cursor in the window, nothing to pick
+    app.world
+        .get_mut::<Window>(window)
+        .unwrap()
+        .set_cursor_position(None);
+    assert!(pick_under_cursor(&mut app.world).is_none());
+}
+
+#[test]
+fn orthographic_near_plane() {
+    // renders from 2 units behind the camera
+    let (mut app, quad) = app(Projection::Orthographic(OrthographicProjection {
+        near: -2.0,
+        scaling_mode: ScalingMode::Fixed {
+            width: 4.0,
+            height: 4.0,
+        },
+        ..default()
+    }));
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    // between the camera and its near plane, in front of the other quad
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let behind = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 6.0)),
+            Hoverable,
+        ))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    let started = drain(&app, &mut starts);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].hovered, behind);
+    let origin = started[0].ray.origin;
+    assert!(
+        origin.abs_diff_eq(Vec3::new(0.0, 0.0, 7.0), 1e-4),
+        "origin {origin}"
+    );
+    let distance = started[0].hit.distance;
+    assert!((distance - 1.0).abs() < 1e-4, "distance {distance}");
+}
+
+#[test]
+fn frame_stamps() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+    run(&mut app, Vec2::splat(0.9));
+
+    app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::ZERO);
+    let frame = app.world.resource::<FrameCount>().0;
+    app.update();
+    let started = drain(&app, &mut starts);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].hovered, quad);
+    assert_eq!(started[0].frame, frame);
+
+    app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::splat(0.9));
+    app.update();
+    let ended = drain(&app, &mut ends);
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].frame, frame + 1);
+}
+
+#[test]
+fn no_camera() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+
+    // e.g. unloaded along with its scene
+    let camera = app
+        .world
+        .query_filtered::<Entity, With<Camera>>()
+        .single(&app.world);
+    app.world.despawn(camera);
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+    assert_eq!(app.world.resource::<Hovered>().inner, None);
+    assert_eq!(drain(&app, &mut ends).len(), 1);
+}
+
+#[derive(Component)]
+struct Enemy;
+
+#[test]
+fn filtered_cast() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.entity_mut(quad).insert(Enemy);
+    // in front of the quad, and not an enemy
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let wall = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 1.0)),
+            Hoverable,
+        ))
+        .id();
+    app.update();
+
+    let ray = Ray {
+        origin: Vec3::new(0.0, 0.0, 5.0),
+        direction: Vec3::NEG_Z,
+    };
+    let hits = cast_ray(&mut app.world, ray);
+    assert_eq!(
+        hits.iter().map(|hit| hit.entity).collect::<Vec<_>>(),
+        [wall, quad]
+    );
+    let hits = cast_ray_filtered::<With<Enemy>>(&mut app.world, ray);
+    assert_eq!(
+        hits.iter().map(|hit| hit.entity).collect::<Vec<_>>(),
+        [quad]
+    );
+    assert!((hits[0].distance - 5.0).abs() < 1e-4);
+}
+
+#[test]
+fn hover_cooldown() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        std::time::Duration::from_millis(100),
+    ));
+    app.world.resource_mut::<PickingSettings>().hover_cooldown =
+        Some(std::time::Duration::from_secs(2));
+    /// Hovers started and ended while the cursor moves to `cursor`, like `run`
+    /// Read after every update, events only last 2 frames
+    fn run_and_drain(app: &mut App, cursor: Vec2) -> (Vec<Entity>, usize) {
+        let mut starts = ManualEventReader::<HoverStart>::default();
+        let mut ends = ManualEventReader::<HoverEnd>::default();
+        // only this run's
+        drain(app, &mut starts);
+        drain(app, &mut ends);
+        app.world.resource_mut::<VirtualCursor>().0 = Some(cursor);
+        let (mut started, mut ended) = (Vec::new(), 0);
+        for _ in 0..3 {
+            app.update();
+            started.extend(drain(app, &mut starts).iter().map(|s| s.hovered));
+            ended += drain(app, &mut ends).len();
+        }
+        (started, ended)
+    }
+
+    // on, off and back on within the cooldown: only the first pass-over starts a hover
+    let (mut started, mut ended) = (Vec::new(), 0);
+    for cursor in [
+        Vec2::ZERO,
+        Vec2::splat(0.9),
+        Vec2::ZERO,
+        Vec2::splat(0.9),
+        Vec2::ZERO,
+    ] {
+        let (s, e) = run_and_drain(&mut app, cursor);
+        started.extend(s);
+        ended += e;
+    }
+    assert_eq!(started, [quad]);
+    assert_eq!(ended, 2);
+    assert!(app.world.get::<Hover>(quad).is_some());
+
+    // past the cooldown, a hover starts again
+    assert_eq!(run_and_drain(&mut app, Vec2::splat(0.9)).1, 1);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(run_and_drain(&mut app, Vec2::ZERO).0, [quad]);
+}
+
+#[test]
+fn hit_triangle_vertices() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    let mut triangle = Mesh::new(PrimitiveTopology::TriangleList);
+    triangle.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, 1.0, 0.0]],
+    );
+    triangle.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+    let triangle = app.world.resource_mut::<Assets<Mesh>>().add(triangle);
+    let transform = Transform::from_xyz(1.0, 0.0, 0.0).with_scale(Vec3::splat(2.0));
+    app.world.spawn((
+        triangle,
+        SpatialBundle::from_transform(transform),
+        Hoverable,
+    ));
+    app.update();
+
+    let ray = Ray {
+        origin: Vec3::new(0.0, -1.0, 5.0),
+        direction: Vec3::NEG_Z,
+    };
+    let hit = cast_ray(&mut app.world, ray)[0];
+    assert_eq!(hit.triangle, Some([0, 1, 2]));
+    let expected = [
+        Vec3::new(-1.0, -2.0, 0.0),
+        Vec3::new(3.0, -2.0, 0.0),
+        Vec3::new(-1.0, 2.0, 0.0),
+    ];
+    let vertices = hit.vertices.unwrap();
+    for (vertex, expected) in vertices.iter().zip(expected) {
+        assert!(vertex.abs_diff_eq(expected, 1e-5), "{vertices:?}");
+    }
+}
+
+#[test]
+fn mirrored_culling() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // back faces are culled by default
+    let front_only = app
+        .world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    app.world.entity_mut(quad).insert(front_only);
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+
+    // mirrored, its winding flips in world space, but the camera still sees its front
+    run(&mut app, Vec2::splat(0.9));
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_scale(Vec3::new(-1.0, 1.0, 1.0)));
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+
+    // mirrored along the view, the camera sees its back
+    run(&mut app, Vec2::splat(0.9));
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_scale(Vec3::new(1.0, 1.0, -1.0)));
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn overlapping_hovers_enter_and_exit() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.resource_mut::<PickingSettings>().hover_band = Some(1.0);
+    // a row of overlapping quads, each covering 2 units of x, staggered in depth
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    app.world.despawn(quad);
+    let [a, b, c] = [-1.0, 0.0, 1.0].map(|x| {
+        app.world
+            .spawn((
+                mesh.clone(),
+                SpatialBundle::from_transform(Transform::from_xyz(x, 0.0, x * 0.1)),
+                Hoverable,
+            ))
+            .id()
+    });
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+    let mut sweep = |app: &mut App, x: f32| {
+        app.world.resource_mut::<PickRayOverride>().0 = Some(PickRay {
+            origin: Vec3::new(x, 0.0, 5.0),
+            direction: Vec3::NEG_Z,
+        });
+        for _ in 0..3 {
+            app.update();
+        }
+        let started: Vec<Entity> = drain(app, &mut starts).iter().map(|s| s.hovered).collect();
+        let ended: Vec<Entity> = drain(app, &mut ends).iter().map(|e| e.hovered).collect();
+        (started, ended)
+    };
+
+    assert_eq!(sweep(&mut app, -1.5), (vec![a], vec![]));
+    assert_eq!(sweep(&mut app, -0.5), (vec![b], vec![]));
+    assert_eq!(sweep(&mut app, 0.5), (vec![c], vec![a]));
+    assert_eq!(sweep(&mut app, 1.5), (vec![], vec![b]));
+    assert_eq!(sweep(&mut app, 3.0), (vec![], vec![c]));
+}
+
+#[test]
+fn pick_radius_in_pixels() {
+    // is the quad hovered with the cursor `pixels` right of its right edge, `depth` from the camera
+    let hovered = |depth: f32, pixels: f32, radius: Option<f32>| {
+        let (mut app, quad) = app(Projection::Perspective(default()));
+        app.world
+            .resource_mut::<PickingSettings>()
+            .pick_radius_pixels = radius;
+        app.world
+            .entity_mut(quad)
+            .insert(Transform::from_xyz(0.0, 0.0, 5.0 - depth));
+        // the default 45 degree field of view, on a square view 100 pixels (2 in NDC) across
+        let edge = 1.0 / (depth * (std::f32::consts::FRAC_PI_8).tan());
+        run(&mut app, Vec2::new(edge + pixels * 0.02, 0.0));
+        app.world.get::<Hover>(quad).is_some()
+    };
+
+    // the same distance on screen picks (or doesn't) at either depth
+    for depth in [5.0, 15.0] {
+        assert!(!hovered(depth, 3.0, None), "{depth}");
+        assert!(hovered(depth, 3.0, Some(5.0)), "{depth}");
+        assert!(!hovered(depth, 3.0, Some(2.0)), "{depth}");
+    }
+}
+
+#[test]
+fn hover_outline() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // off until asked for
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<AabbGizmo>(quad).is_none());
+
+    app.insert_resource(HoverOutline {
+        color: Some(Color::YELLOW),
+    });
+    run(&mut app, Vec2::ZERO);
+    let gizmo = app.world.get::<AabbGizmo>(quad).unwrap();
+    assert_eq!(gizmo.color, Some(Color::YELLOW));
+
+    run(&mut app, Vec2::splat(0.9));
+    assert!(app.world.get::<AabbGizmo>(quad).is_none());
+}
+
+#[cfg(feature = "channel")]
+#[test]
+fn hover_changed_channel() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let receiver = app
+        .world
+        .resource::<bevy_hover::HoverChangedChannel>()
+        .receiver();
+
+    run(&mut app, Vec2::ZERO);
+    run(&mut app, Vec2::splat(0.9));
+    let changes: Vec<(Option<Entity>, Option<Entity>)> =
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|change| (change.from, change.to))
+            .collect();
+    assert_eq!(changes, [(None, Some(quad)), (Some(quad), None)]);
+}
+
+#[test]
+fn picks_unpropagated_spawns() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.update();
+    // spawned after transform propagation, as if earlier in this frame's `Update`
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let moved = app
+        .world
+        .spawn((
+            mesh.clone(),
+            SpatialBundle::from_transform(Transform::from_xyz(3.0, 0.0, 0.0)),
+            Hoverable,
+        ))
+        .id();
+    let child = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 3.0, 0.0)),
+            Hoverable,
+        ))
+        .id();
+    app.world.entity_mut(quad).add_child(child);
+
+    let mut hit_entities = |x: f32| {
+        let ray = Ray {
+            origin: Vec3::new(x, 0.0, 5.0),
+            direction: Vec3::NEG_Z,
+        };
+        let hits = cast_ray(&mut app.world, ray);
+        hits.iter().map(|hit| hit.entity).collect::<Vec<_>>()
+    };
+    // the root is where it's about to be propagated to, the child is at its parent's pose
+    // composed with its own
+    assert_eq!(hit_entities(3.0), [moved]);
+    assert_eq!(hit_entities(0.0), [quad]);
+    let hit = first_hit(&mut app, Vec3::new(0.0, 3.0, 5.0), Vec3::NEG_Z);
+    assert_eq!(hit.map(|hit| hit.entity), Some(child));
+}
+
+#[test]
+fn picks_child_moved_to_identity() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let parent = app
+        .world
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            1.0, 0.0, 0.0,
+        )))
+        .id();
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_xyz(2.0, 0.0, 0.0));
+    app.world.entity_mut(parent).add_child(quad);
+    app.update();
+
+    // moved back over the origin after propagation, its `GlobalTransform` is still at x = 3
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_xyz(-1.0, 0.0, 0.0));
+    let hit = first_hit(&mut app, Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z);
+    assert_eq!(hit.map(|hit| hit.entity), Some(quad));
+    assert!(first_hit(&mut app, Vec3::new(3.0, 0.0, 5.0), Vec3::NEG_Z).is_none());
+}
+
+#[test]
+fn picks_child_cancelling_parent() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // propagated to the identity, which isn't mistaken for an unpropagated child
+    let parent = app
+        .world
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            1.0, 0.0, 0.0,
+        )))
+        .id();
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_xyz(-1.0, 0.0, 0.0));
+    app.world.entity_mut(parent).add_child(quad);
+    app.update();
+    assert_eq!(
+        *app.world.get::<GlobalTransform>(quad).unwrap(),
+        GlobalTransform::IDENTITY
+    );
+
+    let ray = Ray {
+        origin: Vec3::new(0.0, 0.0, 5.0),
+        direction: Vec3::NEG_Z,
+    };
+    let hits = cast_ray(&mut app.world, ray);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entity, quad);
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+}
+
+#[test]
+fn min_triangle_pixels() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    // a sliver about a pixel big, 1 unit in front of a large triangle
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![
+            [-0.02, -0.02, 1.0],
+            [0.03, -0.02, 1.0],
+            [-0.02, 0.03, 1.0],
+            [-1.0, -1.0, 0.0],
+            [2.0, -1.0, 0.0],
+            [-1.0, 2.0, 0.0],
+        ],
+    );
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2, 3, 4, 5])));
+    let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+    let entity = app
+        .world
+        .spawn((mesh, SpatialBundle::default(), Hoverable))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    let hit = app.world.get::<Hover>(entity).unwrap().hit;
+    assert_eq!(hit.triangle, Some([0, 1, 2]));
+
+    app.world
+        .resource_mut::<PickingSettings>()
+        .min_triangle_pixels = Some(4.0);
+    run(&mut app, Vec2::ZERO);
+    let hit = app.world.get::<Hover>(entity).unwrap().hit;
+    assert_eq!(hit.triangle, Some([3, 4, 5]));
+}
+
+#[test]
+fn collider_source() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.entity_mut(quad).insert(HoverColliderSource {
+        path: "collision.glb#Mesh0/Primitive0".to_owned(),
+    });
+    // never loads, there's no such file, the visual quad isn't picked in the meantime
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+
+    // stands in for the loaded collision mesh, twice the size of the visual one
+    let handle = app.world.get::<HoverLod>(quad).unwrap().0.clone();
+    app.world
+        .resource_mut::<Assets<Mesh>>()
+        .insert(&handle, Mesh::from(shape::Quad::new(Vec2::splat(4.0))));
+    // past the edge of the visual quad
+    run(&mut app, Vec2::new(0.7, 0.0));
+    assert!(app.world.get::<Hover>(quad).is_some());
+}
+
+#[test]
+fn screen_closest_pick() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    let mut triangle = |positions: Vec<[f32; 3]>| {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+        let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+        app.world
+            .spawn((mesh, SpatialBundle::default(), Hoverable))
+            .id()
+    };
+    // both under the cursor at (almost) the same depth, only the further one is centered on it
+    let wide = triangle(vec![
+        [-1.0, -1.0, 0.005],
+        [5.0, -1.0, 0.005],
+        [-1.0, 5.0, 0.005],
+    ]);
+    let centered = triangle(vec![[-0.1, -0.1, 0.0], [0.2, -0.1, 0.0], [-0.1, 0.2, 0.0]]);
+
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(wide));
+
+    app.world.resource_mut::<PickingSettings>().pick_mode = PickMode::ScreenClosest;
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(centered));
+}
+
+#[test]
+fn degenerate_triangles_miss() {
+    let hit = |v0: Vec3, v1: Vec3, v2: Vec3| {
+        moller_trumbore(
+            Vec3::new(0.1, 0.1, 5.0),
+            Vec3::NEG_Z,
+            v0,
+            v1,
+            v2,
+            DEFAULT_EPSILON,
+            0.0,
+        )
+    };
+    assert!(hit(Vec3::ZERO, Vec3::X, Vec3::Y).is_some());
+    // two coinciding vertices, any pair of them
+    assert_eq!(hit(Vec3::ZERO, Vec3::ZERO, Vec3::Y), None);
+    assert_eq!(hit(Vec3::ZERO, Vec3::X, Vec3::ZERO), None);
+    assert_eq!(hit(Vec3::ZERO, Vec3::X, Vec3::X), None);
+    // collinear, zero area without any short edge
+    assert_eq!(hit(Vec3::ZERO, Vec3::X, Vec3::X * 2.0), None);
+    // a tiny but proper triangle is still hit, its edges are well over `epsilon` long even
+    // though their squared lengths aren't
+    let small = 0.0005;
+    assert!(hit(
+        Vec3::new(0.1 - small, 0.1 - small, 0.0),
+        Vec3::new(0.1 + 2.0 * small, 0.1 - small, 0.0),
+        Vec3::new(0.1 - small, 0.1 + 2.0 * small, 0.0),
+    )
+    .is_some());
+}
+
+#[test]
+fn several_mouse_rays_skip_picking() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    app.update();
+    // the plugin's startup systems run twice, spawning a second `MouseRay`
+    app.world.run_schedule(Startup);
+
+    // neither the ray nor the hover is updated, both systems skip picking alike
+    let before = *app.world.resource::<PickRay>();
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(*app.world.resource::<PickRay>(), before);
+    assert!(drain(&app, &mut starts).is_empty());
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+/// The GPU backends against the CPU one, in an app with the renderer
+/// Sphere around the entity's origin, intersected analytically
+struct SphereIntersector {
+    radius: f32,
+}
+
+impl HoverIntersector for SphereIntersector {
+    fn intersect(&self, ray: &Ray, transform: &GlobalTransform) -> Option<RayHit> {
+        let center = transform.translation();
+        let direction = ray.direction.normalize();
+        let to_center = center - ray.origin;
+        let along = to_center.dot(direction);
+        let half_chord_squared = self.radius.powi(2) - (to_center.length_squared() - along.powi(2));
+        let distance = along - half_chord_squared.sqrt();
+        // NaN off the sphere
+        (distance > 0.0).then(|| {
+            let point = ray.origin + direction * distance;
+            RayHit {
+                entity: Entity::PLACEHOLDER,
+                distance,
+                point,
+                normal: Some((point - center).normalize()),
+                uv: None,
+                triangle: None,
+                barycentric: None,
+                vertices: None,
+            }
+        })
+    }
+}
+
+#[test]
+fn custom_intersector() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // in front of the quad's center, no mesh
+    let sphere = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 2.0)),
+            HoverIntersect(Arc::new(SphereIntersector { radius: 0.5 })),
+            Hoverable,
+        ))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(sphere));
+    let hit = app.world.get::<Hover>(sphere).unwrap().hit;
+    assert_eq!(hit.entity, sphere);
+    assert!(
+        (hit.distance - 2.5).abs() < 1e-4,
+        "distance {}",
+        hit.distance
+    );
+    assert!(
+        hit.point.abs_diff_eq(Vec3::new(0.0, 0.0, 2.5), 1e-4),
+        "hit {}",
+        hit.point
+    );
+    assert!(hit.normal.unwrap().abs_diff_eq(Vec3::Z, 1e-4));
+
+    // past the sphere's edge, still on the quad behind it
+    run(&mut app, Vec2::new(0.45, 0.0));
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(quad));
+}
+
+#[test]
+fn float32x4_positions() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // the same 2x2 quad with a w component, which is dropped rather than divided by
+    let position = MeshVertexAttribute::new(
+        "Vertex_Position",
+        // the id of `Mesh::ATTRIBUTE_POSITION`, so it's read in place of it
+        0,
+        VertexFormat::Float32x4,
+    );
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        position,
+        VertexAttributeValues::Float32x4(vec![
+            [-1.0, -1.0, 0.0, 2.0],
+            [1.0, -1.0, 0.0, 2.0],
+            [1.0, 1.0, 0.0, 2.0],
+            [-1.0, 1.0, 0.0, 2.0],
+        ]),
+    );
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2, 0, 2, 3])));
+    let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+    app.world.entity_mut(quad).insert(mesh);
+
+    // past the corner of the half size quad dividing by w would make
+    run(&mut app, Vec2::splat(0.4));
+    let point = app.world.get::<Hover>(quad).unwrap().hit.point;
+    assert!(
+        point.abs_diff_eq(Vec3::new(0.83, 0.83, 0.0), 0.01),
+        "hit {point}"
+    );
+    run(&mut app, Vec2::splat(0.9));
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn straddles_orthographic_image_plane() {
+    let (mut app, quad) = app(Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::Fixed {
+            width: 4.0,
+            height: 4.0,
+        },
+        ..default()
+    }));
+    // tilted through the camera's image plane (its near plane, rays start on it), the upper half
+    // in front of the plane and the lower half behind
+    app.world.entity_mut(quad).insert(
+        Transform::from_xyz(0.0, 0.0, 5.0)
+            .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_4)),
+    );
+
+    // 0.5 up, where the quad is half a unit in front of the plane
+    run(&mut app, Vec2::new(0.0, 0.25));
+    let hit = app.world.get::<Hover>(quad).unwrap().hit;
+    assert!(
+        hit.point.abs_diff_eq(Vec3::new(0.0, 0.5, 4.5), 1e-4),
+        "hit {}",
+        hit.point
+    );
+    assert!(
+        (hit.distance - 0.5).abs() < 1e-4,
+        "distance {}",
+        hit.distance
+    );
+    // 0.5 down it's behind
+    run(&mut app, Vec2::new(0.0, -0.25));
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn point_in_closed_mesh() {
+    let cube = Mesh::from(shape::Cube { size: 2.0 });
+    // spans x -1 to 3, y and z -2 to 2
+    let transform =
+        GlobalTransform::from(Transform::from_xyz(1.0, 0.0, 0.0).with_scale(Vec3::splat(2.0)));
+    for inside in [
+        Vec3::ZERO,
+        Vec3::new(2.5, 1.5, -1.5),
+        Vec3::new(-0.9, -1.9, 1.9),
+    ] {
+        assert!(point_in_mesh(inside, &cube, &transform), "{inside}");
+    }
+    for outside in [
+        Vec3::new(3.5, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 2.5),
+        Vec3::new(-3.0, -3.0, -3.0),
+        Vec3::new(10.0, 10.0, 10.0),
+    ] {
+        assert!(!point_in_mesh(outside, &cube, &transform), "{outside}");
+    }
+}
+
+#[test]
+fn closest_point_on_triangle_face_and_edges() {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+    );
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+    let transform = GlobalTransform::from(Transform::from_xyz(0.0, 0.0, 1.0));
+
+    // projects inside the face: straight down onto it
+    let (point, distance) =
+        closest_surface_point(Vec3::new(0.5, 0.5, 3.0), &mesh, &transform).unwrap();
+    assert!(point.abs_diff_eq(Vec3::new(0.5, 0.5, 1.0), 1e-5), "{point}");
+    assert!((distance - 2.0).abs() < 1e-5, "{distance}");
+
+    // projects past the long edge, onto its middle
+    let (point, distance) =
+        closest_surface_point(Vec3::new(1.5, 1.5, 1.0), &mesh, &transform).unwrap();
+    assert!(point.abs_diff_eq(Vec3::new(1.0, 1.0, 1.0), 1e-5), "{point}");
+    assert!((distance - 0.5_f32.sqrt()).abs() < 1e-5, "{distance}");
+
+    // past a corner, onto the vertex
+    let (point, distance) =
+        closest_surface_point(Vec3::new(3.0, -1.0, 2.0), &mesh, &transform).unwrap();
+    assert!(point.abs_diff_eq(Vec3::new(2.0, 0.0, 1.0), 1e-5), "{point}");
+    assert!((distance - 3.0_f32.sqrt()).abs() < 1e-5, "{distance}");
+}
+
+#[test]
+fn time_sliced_sweep() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world
+        .resource_mut::<PickingSettings>()
+        .max_entities_per_frame = Some(2);
+    // 5 quads in a row toward the camera, all under the cursor
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let quads = [0.5, 1.0, 1.5, 2.0].map(|z| {
+        app.world
+            .spawn((
+                mesh.clone(),
+                SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, z)),
+                Hoverable,
+            ))
+            .id()
+    });
+
+    app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::ZERO);
+    // hoverables tested or rejected in each frame of the sweep
+    let mut slices = Vec::new();
+    for _ in 0..10 {
+        app.update();
+        let stats = *app.world.resource::<PickingStats>();
+        let covered = stats.entities_tested + stats.broad_phase_rejected;
+        if covered > 0 {
+            slices.push(covered);
+        }
+        if app.world.resource::<Hovered>().inner.is_some() {
+            break;
+        }
+    }
+    // nothing is hovered until every quad has been covered, 2 a frame
+    assert_eq!(slices, [2, 2, 1]);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(quads[3]));
+}
+
+#[test]
+fn async_raycast() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // the quad's transform is propagated
+    app.update();
+    let raycast = app
+        .world
+        .spawn(AsyncRaycast {
+            ray: Ray {
+                origin: Vec3::new(0.5, 0.5, 3.0),
+                direction: Vec3::NEG_Z,
+            },
+        })
+        .id();
+
+    // the task runs on another thread, keep updating until it's polled as finished
+    let result = (0..1000).find_map(|_| {
+        app.update();
+        let hit = app
+            .world
+            .get::<AsyncRaycastResult>(raycast)
+            .map(|result| result.hit);
+        if hit.is_none() {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        hit
+    });
+    let hit = result
+        .expect("no AsyncRaycastResult")
+        .expect("missed the quad");
+    assert_eq!(hit.entity, quad);
+    assert!(
+        (hit.distance - 3.0).abs() < 1e-4,
+        "distance {}",
+        hit.distance
+    );
+    assert!(
+        hit.point.abs_diff_eq(Vec3::new(0.5, 0.5, 0.0), 1e-4),
+        "hit {}",
+        hit.point
+    );
+}
+
+#[test]
+fn polling_hover_start() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.resource_mut::<PickingSettings>().mode = HoverMode::Polling;
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+    drain(&app, &mut starts);
+    // the cursor stays on the quad: one HoverStart a frame, and never a HoverEnd
+    for _ in 0..5 {
+        let frame = app.world.resource::<FrameCount>().0;
+        app.update();
+        let started = drain(&app, &mut starts);
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].hovered, quad);
+        assert_eq!(started[0].frame, frame);
+    }
+    assert!(drain(&app, &mut ends).is_empty());
+
+    // off it, no more
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(drain(&app, &mut ends).len(), 1);
+    drain(&app, &mut starts);
+    app.update();
+    assert!(drain(&app, &mut starts).is_empty());
+}
+
+#[test]
+fn orthographic_near_offset() {
+    let (mut app, quad) = app(Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::Fixed {
+            width: 4.0,
+            height: 4.0,
+        },
+        ..default()
+    }));
+    // behind the camera's image plane, over the other quad
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let behind = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 6.0)),
+            Hoverable,
+        ))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(quad));
+
+    // rays now start 2 units back, past it
+    app.world
+        .resource_mut::<PickingSettings>()
+        .ortho_near_offset = 2.0;
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(behind));
+    let point = app.world.get::<Hover>(behind).unwrap().hit.point;
+    assert!(
+        point.abs_diff_eq(Vec3::new(0.0, 0.0, 6.0), 1e-4),
+        "hit {point}"
+    );
+}
+
+#[test]
+fn far_plane_clamp() {
+    /// true if the quad is hovered `depth` in front of the camera
+    fn hovered_at(app: &mut App, quad: Entity, depth: f32) -> bool {
+        app.world
+            .entity_mut(quad)
+            .insert(Transform::from_xyz(0.0, 0.0, 5.0 - depth));
+        run(app, Vec2::ZERO);
+        app.world.get::<Hover>(quad).is_some()
+    }
+
+    let (mut app, quad) = app(Projection::Perspective(PerspectiveProjection {
+        far: 10.0,
+        ..default()
+    }));
+    app.world
+        .resource_mut::<PickingSettings>()
+        .clamp_to_clip_planes = true;
+    // just inside the far plane, and just past it
+    assert!(hovered_at(&mut app, quad, 9.9));
+    assert!(!hovered_at(&mut app, quad, 10.1));
+    // rendered or not, it's still there without the clamp
+    app.world
+        .resource_mut::<PickingSettings>()
+        .clamp_to_clip_planes = false;
+    assert!(hovered_at(&mut app, quad, 10.1));
+}
+
+#[test]
+fn static_frame_keeps_ray() {
+    let (mut app, _) = app(Projection::Perspective(default()));
+    run(&mut app, Vec2::ZERO);
+    let ray = *app.world.resource::<PickRay>();
+
+    // only a recomputed ray would overwrite this
+    let stale = PickRay {
+        origin: Vec3::splat(100.0),
+        direction: Vec3::X,
+    };
+    *app.world.resource_mut::<PickRay>() = stale;
+    app.update();
+    assert_eq!(*app.world.resource::<PickRay>(), stale);
+
+    // the cursor moved, so did the ray
+    run(&mut app, Vec2::new(0.5, 0.0));
+    let moved = *app.world.resource::<PickRay>();
+    assert_ne!(moved, stale);
+    assert_ne!(moved, ray);
+
+    // and again when the camera moves
+    *app.world.resource_mut::<PickRay>() = stale;
+    let mut camera = app.world.query_filtered::<&mut Transform, With<Camera>>();
+    camera.single_mut(&mut app.world).translation.x = 1.0;
+    run(&mut app, Vec2::new(0.5, 0.0));
+    assert_ne!(*app.world.resource::<PickRay>(), stale);
+}
+
+#[test]
+fn lerp_and_inverse_lerp() {
+    let (a, b) = (2.0_f32, 6.0_f32);
+    // inside the range, and extrapolated past either end
+    assert_eq!(inverse_lerp(a, b, 3.0), 0.25);
+    assert_eq!(inverse_lerp(a, b, 10.0), 2.0);
+    assert_eq!(inverse_lerp(a, b, 0.0), -0.5);
+    assert_eq!(inverse_lerp(b, a, 10.0), -1.0);
+    assert_eq!(lerp(a, b, -0.5), 0.0);
+    // empty range
+    assert_eq!(inverse_lerp(a, a, 5.0), 0.0);
+
+    for value in [-7.5, 0.0, 2.0, 4.5, 6.0, 20.0] {
+        let t = inverse_lerp(a, b, value);
+        assert!((lerp(a, b, t) - value).abs() < 1e-5, "{value}");
+    }
+    let (a, b) = (Vec3::new(1.0, -2.0, 0.5), Vec3::new(-3.0, 4.0, 2.0));
+    for t in [-1.5, 0.0, 0.3, 1.0, 2.5] {
+        assert!((inverse_lerp(a, b, lerp(a, b, t)) - t).abs() < 1e-5, "{t}");
+    }
+
+    // map is built on them
+    assert_eq!(5.0_f32.map((0.0, 10.0), (100.0, 200.0)), 150.0);
+    assert_eq!(15.0_f32.map_clamped((0.0, 10.0), (100.0, 200.0)), 200.0);
+}
+
+#[derive(Resource, Default)]
+struct Pressed(Vec<Entity>);
+
+#[test]
+fn press_handler() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.init_resource::<Pressed>()
+        .add_hover_press_handler(|entity, world| world.resource_mut::<Pressed>().0.push(entity));
+    let click = |app: &mut App| {
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        let mut input = app.world.resource_mut::<Input<MouseButton>>();
+        input.clear();
+        input.release(MouseButton::Left);
+        app.update();
+        app.world.resource_mut::<Input<MouseButton>>().clear();
+    };
+
+    // nothing hovered, nothing pressed
+    run(&mut app, Vec2::splat(0.9));
+    click(&mut app);
+    assert!(app.world.resource::<Pressed>().0.is_empty());
+
+    run(&mut app, Vec2::ZERO);
+    click(&mut app);
+    assert_eq!(app.world.resource::<Pressed>().0, [quad]);
+}
+
+#[test]
+fn non_uniform_scale_distance() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world
+        .entity_mut(quad)
+        .insert(Transform::from_xyz(0.0, 0.0, 0.25));
+    // a 2x2x2 cube flattened along the ray and stretched across it: its front face is at z 0.5,
+    // just in front of the quad. Scaling the model space distance by its x or y scale would put
+    // it far behind
+    let cube = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Mesh::from(shape::Cube { size: 2.0 }));
+    let stretched = app
+        .world
+        .spawn((
+            cube,
+            SpatialBundle::from_transform(Transform::from_scale(Vec3::new(4.0, 4.0, 0.5))),
+            Hoverable,
+        ))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(stretched));
+    let hit = app.world.get::<Hover>(stretched).unwrap().hit;
+    assert!(
+        (hit.distance - 4.5).abs() < 1e-4,
+        "distance {}",
+        hit.distance
+    );
+    assert!(
+        hit.point.abs_diff_eq(Vec3::new(0.0, 0.0, 0.5), 1e-4),
+        "hit {}",
+        hit.point
+    );
+
+    // pushed back so its front face is behind the quad
+    app.world
+        .entity_mut(stretched)
+        .insert(Transform::from_xyz(0.0, 0.0, -0.5).with_scale(Vec3::new(4.0, 4.0, 0.5)));
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(quad));
+}
+
+#[test]
+fn strip_matches_list() {
+    /// Two unit quads either side of the origin, as `topology` with `indices`
+    fn quads(topology: PrimitiveTopology, indices: Vec<u16>) -> Mesh {
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        for x in [-1.0, 1.0] {
+            for [dx, dy] in [[-0.5, -0.5], [0.5, -0.5], [-0.5, 0.5], [0.5, 0.5]] {
+                positions.push([x + dx, dy, 0.0]);
+            }
+        }
+        let mut mesh = Mesh::new(topology);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.set_indices(Some(Indices::U16(indices)));
+        mesh
+    }
+    let list = quads(
+        PrimitiveTopology::TriangleList,
+        vec![0, 1, 2, 2, 1, 3, 4, 5, 6, 6, 5, 7],
+    );
+    // one strip per quad, split by a primitive restart
+    let strip = quads(
+        PrimitiveTopology::TriangleStrip,
+        vec![0, 1, 2, 3, u16::MAX, 4, 5, 6, 7],
+    );
+
+    // the same triangles, wound the same way
+    let triangles = |mesh: &Mesh| mesh_triangle_iter(mesh).unwrap().collect::<Vec<_>>();
+    assert_eq!(triangles(&list).len(), 4);
+    assert_eq!(triangles(&strip), triangles(&list));
+
+    // and the same hits: on each quad, and between them
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut hits = |mesh: Mesh| {
+        let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+        app.world.entity_mut(quad).insert(mesh);
+        [-0.48, 0.48, 0.0].map(|x| {
+            run(&mut app, Vec2::new(x, 0.0));
+            app.world
+                .get::<Hover>(quad)
+                .map(|hover| (hover.hit.point, hover.hit.triangle))
+        })
+    };
+    let list_hits = hits(list);
+    assert!(list_hits[0].is_some() && list_hits[1].is_some() && list_hits[2].is_none());
+    assert_eq!(hits(strip), list_hits);
+}
+
+#[test]
+fn lod_ignores_render_mesh_swaps() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // picked as the 2x2 quad whatever is rendered
+    let lod = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    app.world.entity_mut(quad).insert(HoverLod(lod));
+
+    for size in [0.5, 4.0, 1.0] {
+        // the visual LOD switching, e.g. as the camera zooms
+        let render = app
+            .world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(shape::Quad::new(Vec2::splat(size))));
+        app.world.entity_mut(quad).insert(render);
+
+        // inside the 2x2 quad, and outside it
+        run(&mut app, Vec2::new(0.3, 0.0));
+        assert!(app.world.get::<Hover>(quad).is_some(), "render size {size}");
+        run(&mut app, Vec2::new(0.7, 0.0));
+        assert!(app.world.get::<Hover>(quad).is_none(), "render size {size}");
+    }
+}
+
+#[test]
+fn epsilon_rejects_near_parallel_rays() {
+    // skims the triangles' plane, dropping 0.001 for every unit across it
+    let direction = Vec3::new(1.0, 0.0, -0.001).normalize();
+    let hit = |epsilon| {
+        moller_trumbore(
+            Vec3::new(0.0, 0.5, 0.0005),
+            direction,
+            Vec3::ZERO,
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            epsilon,
+            0.0,
+        )
+    };
+    assert!(hit(DEFAULT_EPSILON).is_some());
+    assert!(hit(0.01).is_none());
+
+    // the same through `PickingSettings`
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.resource_mut::<PickRayOverride>().0 = Some(PickRay {
+        origin: Vec3::new(-0.5, 0.3, 0.0005),
+        direction,
+    });
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_some());
+    app.world.resource_mut::<PickingSettings>().epsilon = 0.01;
+    run(&mut app, Vec2::ZERO);
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn hover_start_texel() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    let texture = app
+        .world
+        .resource_mut::<Assets<Image>>()
+        .add(Image::new_fill(
+            Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[200, 100, 50, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        ));
+    let material = app
+        .world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color_texture: Some(texture),
+            ..default()
+        });
+    app.world.entity_mut(quad).insert(material);
+
+    // not sampled unless asked for
+    run(&mut app, Vec2::ZERO);
+    let started = drain(&app, &mut starts);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].texel, None);
+
+    app.world.resource_mut::<PickingSettings>().sample_texels = true;
+    run(&mut app, Vec2::splat(0.9));
+    run(&mut app, Vec2::new(0.2, -0.3));
+    let started = drain(&app, &mut starts);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].texel, Some(Color::rgba_u8(200, 100, 50, 255)));
+}
+
+#[test]
+fn coincident_hits_tiebreak() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // more copies of the quad in the same spot, one of them reusing a despawned entity's index
+    // so entity order differs from spawn order
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let spawn = |app: &mut App| {
+        app.world
+            .spawn((mesh.clone(), SpatialBundle::default(), Hoverable))
+            .id()
+    };
+    let despawned = spawn(&mut app);
+    spawn(&mut app);
+    app.world.despawn(despawned);
+    spawn(&mut app);
+
+    let mut expected: Vec<Entity> = app
+        .world
+        .query_filtered::<Entity, With<Hoverable>>()
+        .iter(&app.world)
+        .collect();
+    expected.sort();
+    run(&mut app, Vec2::ZERO);
+    let ray = Ray {
+        origin: Vec3::new(0.3, 0.2, 5.0),
+        direction: Vec3::NEG_Z,
+    };
+    for _ in 0..3 {
+        let hits = cast_ray(&mut app.world, ray);
+        let order: Vec<Entity> = hits.iter().map(|hit| hit.entity).collect();
+        assert_eq!(order, expected);
+        assert!(hits.iter().all(|hit| hit.distance == hits[0].distance));
+        // the hover pass breaks the tie the same way
+        assert_eq!(app.world.resource::<Hovered>().inner, Some(expected[0]));
+        app.update();
+    }
+}
+
+#[test]
+fn camera_on_moving_rig() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // the camera keeps its transform, now relative to the rig
+    let camera = app
+        .world
+        .query_filtered::<Entity, With<Camera>>()
+        .single(&app.world);
+    let rig = app.world.spawn(SpatialBundle::default()).id();
+    app.world.entity_mut(rig).add_child(camera);
+    run(&mut app, Vec2::ZERO);
+
+    // orbiting the quad: the ray follows the rig the same frame it moves
+    for step in 0..6 {
+        let rotation = Quat::from_rotation_y((step as f32 * 10.0 - 25.0).to_radians());
+        app.world.get_mut::<Transform>(rig).unwrap().rotation = rotation;
+        app.update();
+        let origin = app.world.resource::<PickRay>().origin;
+        let expected = rotation * Vec3::new(0.0, 0.0, 5.0);
+        assert!(
+            origin.abs_diff_eq(expected, 1e-4),
+            "origin {origin}, expected {expected}"
+        );
+        let point = app.world.get::<Hover>(quad).unwrap().hit.point;
+        assert!(point.abs_diff_eq(Vec3::ZERO, 1e-4), "hit {point}");
+    }
+
+    // carried off to the side, looking past the quad
+    *app.world.get_mut::<Transform>(rig).unwrap() = Transform::from_xyz(3.0, 0.0, 0.0);
+    app.update();
+    assert!(app.world.get::<Hover>(quad).is_none());
+}
+
+#[test]
+fn cursor_conventions() {
+    // a 100x100 window, the size of the camera's image, with the cursor at its center and a
+    // quarter of the way up from it
+    for (convention, center, up) in [
+        (
+            CursorConvention::default(),
+            Vec2::new(50.0, 50.0),
+            Vec2::new(50.0, 25.0),
+        ),
+        (
+            CursorConvention {
+                origin: CursorOrigin::TopLeft,
+                y_up: true,
+            },
+            Vec2::new(50.0, -50.0),
+            Vec2::new(50.0, -25.0),
+        ),
+        (
+            CursorConvention {
+                origin: CursorOrigin::Center,
+                y_up: false,
+            },
+            Vec2::ZERO,
+            Vec2::new(0.0, -25.0),
+        ),
+        (
+            CursorConvention {
+                origin: CursorOrigin::Center,
+                y_up: true,
+            },
+            Vec2::ZERO,
+            Vec2::new(0.0, 25.0),
+        ),
+    ] {
+        let (mut app, _) = app(Projection::Perspective(default()));
+        app.world
+            .resource_mut::<PickingSettings>()
+            .cursor_convention = convention;
+        let window = app
+            .world
+            .spawn(Window {
+                resolution: (100.0, 100.0).into(),
+                ..default()
+            })
+            .id();
+        let mut direction_at = |position| {
+            app.world.send_event(CursorMoved { window, position });
+            app.update();
+            app.world.resource::<PickRay>().direction.normalize()
+        };
+
+        // NDC (0, 0), straight down the camera's view
+        let direction = direction_at(center);
+        assert!(
+            direction.abs_diff_eq(Vec3::NEG_Z, 1e-4),
+            "{convention:?}: {direction}"
+        );
+        // NDC (0, 0.5)
+        let direction = direction_at(up);
+        let expected = Vec3::new(0.0, 0.5 * std::f32::consts::FRAC_PI_8.tan(), -1.0).normalize();
+        assert!(
+            direction.abs_diff_eq(expected, 1e-4),
+            "{convention:?}: {direction}"
+        );
+    }
+}
+
+/// Every `CurrentHover` change detection reported, in order
+#[derive(Resource, Default)]
+struct CurrentHoverChanges(Vec<Option<Entity>>);
+
+fn record_current_hover_changes(
+    changed: Query<&CurrentHover, Changed<CurrentHover>>,
+    mut changes: ResMut<CurrentHoverChanges>,
+) {
+    changes.0.extend(changed.iter().map(|current| current.0));
+}
+
+#[test]
+fn current_hover_changes_on_transitions() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.init_resource::<CurrentHoverChanges>()
+        .add_systems(Last, record_current_hover_changes);
+    // whatever was reported while the component was added
+    run(&mut app, Vec2::splat(0.9));
+    app.world.resource_mut::<CurrentHoverChanges>().0.clear();
+
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<CurrentHoverChanges>().0, [Some(quad)]);
+    // still on the quad, and moving across it: no changes
+    run(&mut app, Vec2::new(0.1, 0.1));
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(app.world.resource::<CurrentHoverChanges>().0, [Some(quad)]);
+
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(
+        app.world.resource::<CurrentHoverChanges>().0,
+        [Some(quad), None]
+    );
+}
+
+#[test]
+fn occluder_blocks_hover() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    // a wall between the camera and the quad, not hoverable itself
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let wall = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 2.0)),
+            Occluder,
+        ))
+        .id();
+
+    run(&mut app, Vec2::ZERO);
+    assert!(drain(&app, &mut starts).is_empty());
+    assert_eq!(app.world.resource::<Hovered>().inner, None);
+
+    // out of the way, the quad is hovered again
+    app.world
+        .entity_mut(wall)
+        .insert(Transform::from_xyz(10.0, 0.0, 2.0));
+    run(&mut app, Vec2::ZERO);
+    let started = drain(&app, &mut starts);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].hovered, quad);
+}
+
+#[test]
+fn pick_modes_on_a_stack() {
+    let (mut app, back) = app(Projection::Perspective(default()));
+    let mesh = app.world.get::<Handle<Mesh>>(back).unwrap().clone();
+    let [middle, front] = [1.0, 2.0].map(|z| {
+        app.world
+            .spawn((
+                mesh.clone(),
+                SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, z)),
+                Hoverable,
+            ))
+            .id()
+    });
+    let mut hovered_with = |mode| {
+        app.world.resource_mut::<PickingSettings>().pick_mode = mode;
+        run(&mut app, Vec2::ZERO);
+        let tested = app.world.resource::<PickingStats>().entities_tested;
+        (app.world.resource::<Hovered>().inner.unwrap(), tested)
+    };
+
+    assert_eq!(hovered_with(PickMode::Nearest).0, front);
+    assert_eq!(hovered_with(PickMode::Farthest).0, back);
+    // any of them, as long as it stopped there
+    let (first, tested) = hovered_with(PickMode::FirstHit);
+    assert!([back, middle, front].contains(&first));
+    assert_eq!(tested, 1);
+}
+
+/// App with `collider` on the only hoverable, at `transform`
+fn collider_app(collider: impl Component, transform: Transform) -> App {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    app.world.spawn((
+        collider,
+        SpatialBundle::from_transform(transform),
+        Hoverable,
+    ));
+    app.update();
+    app
+}
+
+/// Where a ray from `origin` towards `direction` first hits a hoverable, None for a miss
+fn first_hit(app: &mut App, origin: Vec3, direction: Vec3) -> Option<RayHit> {
+    let ray = Ray {
+        origin,
+        direction: direction.normalize(),
+    };
+    cast_ray(&mut app.world, ray).first().copied()
+}
+
+/// Asserts a ray from `origin` towards `direction` first hits a hoverable at `point`
+fn assert_hits(app: &mut App, origin: Vec3, direction: Vec3, point: Vec3) {
+    let hit = first_hit(app, origin, direction)
+        .unwrap_or_else(|| panic!("{origin} towards {direction} missed"));
+    assert!(
+        hit.point.abs_diff_eq(point, 1e-4),
+        "{origin} towards {direction} hit {}",
+        hit.point
+    );
+    assert!(
+        (hit.distance - origin.distance(point)).abs() < 1e-4,
+        "{origin} towards {direction} at {}",
+        hit.distance
+    );
+}
+
+#[test]
+fn obb_collider() {
+    // turned a quarter around Y: local X runs along world -Z, local Z along world X
+    // so it spans x 1.75 to 2.25, y -0.5 to 0.5 and z -1 to 1
+    let mut app = collider_app(
+        HoverObb {
+            half_extents: Vec3::new(1.0, 0.5, 0.25),
+        },
+        Transform::from_xyz(2.0, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+    );
+    for (origin, point) in [
+        (Vec3::new(10.0, 0.2, 0.3), Vec3::new(2.25, 0.2, 0.3)),
+        (Vec3::new(-10.0, 0.2, 0.3), Vec3::new(1.75, 0.2, 0.3)),
+        (Vec3::new(2.1, 10.0, -0.5), Vec3::new(2.1, 0.5, -0.5)),
+        (Vec3::new(2.1, -10.0, 0.5), Vec3::new(2.1, -0.5, 0.5)),
+        (Vec3::new(2.1, 0.2, 10.0), Vec3::new(2.1, 0.2, 1.0)),
+        (Vec3::new(2.1, 0.2, -10.0), Vec3::new(2.1, 0.2, -1.0)),
+    ] {
+        // straight at each face
+        let direction = (point - origin).normalize();
+        assert_hits(&mut app, origin, direction, point);
+    }
+    // within the box's half length of its center, but past its rotated half width
+    assert!(first_hit(&mut app, Vec3::new(2.4, 0.0, 10.0), Vec3::NEG_Z).is_none());
+}
+
+#[test]
+fn capsule_collider() {
+    // along Y, the body spans y -1 to 1 and the caps reach 1.5
+    let mut app = collider_app(
+        HoverCapsule {
+            radius: 0.5,
+            half_height: 1.0,
+        },
+        Transform::IDENTITY,
+    );
+    // the body, then the top cap head on and the side of the bottom cap, 0.3 off its center
+    assert_hits(
+        &mut app,
+        Vec3::new(0.0, 0.5, 10.0),
+        Vec3::NEG_Z,
+        Vec3::new(0.0, 0.5, 0.5),
+    );
+    assert_hits(
+        &mut app,
+        Vec3::new(0.0, 10.0, 0.0),
+        Vec3::NEG_Y,
+        Vec3::new(0.0, 1.5, 0.0),
+    );
+    assert_hits(
+        &mut app,
+        Vec3::new(0.0, -1.3, 10.0),
+        Vec3::NEG_Z,
+        Vec3::new(0.0, -1.3, 0.4),
+    );
+    // past the top cap, and beside the body
+    assert!(first_hit(&mut app, Vec3::new(0.0, 1.6, 10.0), Vec3::NEG_Z).is_none());
+    assert!(first_hit(&mut app, Vec3::new(0.6, 0.0, 10.0), Vec3::NEG_Z).is_none());
+}
+
+#[test]
+fn convex_hull_collider() {
+    // the corner of the unit cube cut off by x + y + z = 1, wound to face out
+    let mut tetrahedron = Mesh::new(PrimitiveTopology::TriangleList);
+    tetrahedron.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ],
+    );
+    tetrahedron.set_indices(Some(Indices::U32(vec![0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3])));
+    let hull = HoverConvexHull::from_mesh(&tetrahedron).unwrap();
+    assert_eq!(hull.planes.len(), 4);
+    let mut app = collider_app(hull, Transform::IDENTITY);
+    let third = Vec3::splat(1.0 / 3.0);
+    for (origin, point) in [
+        (Vec3::new(0.2, 0.2, -5.0), Vec3::new(0.2, 0.2, 0.0)),
+        (Vec3::new(-5.0, 0.2, 0.2), Vec3::new(0.0, 0.2, 0.2)),
+        (Vec3::new(0.2, -5.0, 0.2), Vec3::new(0.2, 0.0, 0.2)),
+        (Vec3::splat(2.0), third),
+    ] {
+        assert_hits(&mut app, origin, point - origin, point);
+    }
+    // inside the bounding cube, outside the slanted face
+    assert!(first_hit(&mut app, Vec3::new(0.8, 0.8, -5.0), Vec3::Z).is_none());
+}
+
+#[test]
+fn plane_collider() {
+    let mut app = collider_app(HoverPlane::infinite(Vec3::Y), Transform::IDENTITY);
+    // however far off the origin
+    assert_hits(
+        &mut app,
+        Vec3::new(100.0, 5.0, -40.0),
+        Vec3::NEG_Y,
+        Vec3::new(100.0, 0.0, -40.0),
+    );
+    assert!(first_hit(&mut app, Vec3::new(0.0, 5.0, 0.0), Vec3::Y).is_none());
+
+    // 2 along X and 4 along Z, raised to y = 1
+    let mut app = collider_app(
+        HoverPlane::bounded(Vec3::Y, Vec2::new(1.0, 2.0)),
+        Transform::from_xyz(0.0, 1.0, 0.0),
+    );
+    assert_hits(
+        &mut app,
+        Vec3::new(0.5, 5.0, 1.5),
+        Vec3::NEG_Y,
+        Vec3::new(0.5, 1.0, 1.5),
+    );
+    assert!(first_hit(&mut app, Vec3::new(1.5, 5.0, 0.0), Vec3::NEG_Y).is_none());
+    assert!(first_hit(&mut app, Vec3::new(0.5, 5.0, 2.5), Vec3::NEG_Y).is_none());
+}
+
+#[test]
+fn heightfield_collider() {
+    // 2x2 cells spanning -1 to 1, flat but for a peak of 1 in the middle
+    let terrain = HoverHeightfield {
+        heights: vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+        columns: 3,
+        cell_size: Vec2::ONE,
+    };
+    assert_eq!(terrain.rows(), 3);
+    assert_eq!(terrain.height_at(Vec2::ZERO), Some(1.0));
+    assert_eq!(terrain.height_at(Vec2::new(1.0, -1.0)), Some(0.0));
+    // halfway down the peak, on either side of the cell diagonals
+    assert!((terrain.height_at(Vec2::new(0.5, 0.5)).unwrap() - 0.5).abs() < 1e-5);
+    assert!((terrain.height_at(Vec2::new(-0.5, 0.0)).unwrap() - 0.5).abs() < 1e-5);
+    assert!((terrain.height_at(Vec2::new(0.25, -0.5)).unwrap() - 0.25).abs() < 1e-5);
+    assert_eq!(terrain.height_at(Vec2::new(2.0, 0.0)), None);
+
+    let mut app = collider_app(terrain.clone(), Transform::IDENTITY);
+    assert_hits(
+        &mut app,
+        Vec3::new(0.5, 10.0, 0.5),
+        Vec3::NEG_Y,
+        Vec3::new(0.5, 0.5, 0.5),
+    );
+    // slanting down over the first cell into the slope of the (0, 1) cell
+    assert_hits(
+        &mut app,
+        Vec3::new(-2.0, 2.0, 0.25),
+        Vec3::new(1.0, -1.0, 0.0),
+        Vec3::new(-0.375, 0.375, 0.25),
+    );
+    let hit = first_hit(
+        &mut app,
+        Vec3::new(-0.9, 3.0, -0.8),
+        Vec3::new(1.0, -2.0, 0.5),
+    )
+    .unwrap();
+    let height = terrain.height_at(hit.point.xz()).unwrap();
+    assert!(
+        (hit.point.y - height).abs() < 1e-4,
+        "{} vs {height}",
+        hit.point
+    );
+    // off the grid
+    assert!(first_hit(&mut app, Vec3::new(3.0, 10.0, 0.0), Vec3::NEG_Y).is_none());
+}
+
+#[test]
+fn instanced_meshes() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Mesh::from(shape::Quad::new(Vec2::splat(0.2))));
+    // 10x10 instances of the one mesh, 0.3 apart around the origin
+    let instances: Vec<Entity> = (0..100)
+        .map(|i| {
+            let position =
+                Vec3::new((i % 10) as f32, (i / 10) as f32, 0.0) * 0.3 - Vec3::new(1.35, 1.35, 0.0);
+            app.world
+                .spawn((
+                    mesh.clone(),
+                    SpatialBundle::from_transform(Transform::from_translation(position)),
+                    Hoverable,
+                ))
+                .id()
+        })
+        .collect();
+    app.update();
+    assert_eq!(app.world.resource::<PickingStats>().meshes_built, 1);
+
+    // the view's half extent at the instances' depth
+    let half_extent = 5.0 * std::f32::consts::FRAC_PI_8.tan();
+    for (instance, position) in [(72, Vec2::new(-0.75, 0.75)), (27, Vec2::new(0.75, -0.75))] {
+        run(&mut app, position / half_extent);
+        assert_eq!(
+            app.world.resource::<Hovered>().inner,
+            Some(instances[instance])
+        );
+        assert_eq!(app.world.resource::<PickingStats>().meshes_built, 0);
+    }
+}
+
+#[test]
+fn line_mesh_pixel_threshold() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    let mut line = Mesh::new(PrimitiveTopology::LineList);
+    line.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[-1.0, 0.0, 0.0], [1.0, 0.0, 0.0]],
+    );
+    let line = app.world.resource_mut::<Assets<Mesh>>().add(line);
+    let line = app
+        .world
+        .spawn((line, SpatialBundle::default(), Hoverable))
+        .id();
+    // 50 pixels from the center of the 100 pixel target to the edge of the view
+    let pixels = |pixels: f32| Vec2::new(0.0, pixels / 50.0);
+
+    // unpickable by default
+    run(&mut app, pixels(0.0));
+    assert_eq!(app.world.resource::<Hovered>().inner, None);
+
+    app.world.resource_mut::<PickingSettings>().line_pick_pixels = Some(3.0);
+    for (offset, hovered) in [(0.0, true), (2.0, true), (-2.0, true), (5.0, false)] {
+        run(&mut app, pixels(offset));
+        assert_eq!(
+            app.world.resource::<Hovered>().inner,
+            hovered.then_some(line),
+            "{offset} pixels off"
+        );
+    }
+}
+
+#[test]
+fn screen_ndc_to_ray_by_projection() {
+    /// `screen_ndc_to_ray` through `ndc` of the app's camera, once it's at `transform`
+    fn ray_through(app: &mut App, transform: Transform, ndc: Vec2) -> Ray {
+        let camera = app
+            .world
+            .query_filtered::<Entity, With<Camera>>()
+            .single(&app.world);
+        *app.world.get_mut::<Transform>(camera).unwrap() = transform;
+        app.update();
+        let (camera, projection, transform) = app
+            .world
+            .query::<(&Camera, &Projection, &GlobalTransform)>()
+            .single(&app.world);
+        screen_ndc_to_ray(camera, projection, transform, ndc)
+    }
+    fn assert_ray(ray: Ray, origin: Vec3, direction: Vec3) {
+        assert!(ray.origin.abs_diff_eq(origin, 1e-4), "{ray:?}");
+        assert!(
+            ray.direction.abs_diff_eq(direction.normalize(), 1e-4),
+            "{ray:?}"
+        );
+    }
+    let ndc = Vec2::new(0.5, -0.25);
+    let facing_z = Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+    // looking down -X, so the view's right is -Z
+    let facing_x = Transform::from_xyz(3.0, 0.0, 0.0).looking_at(Vec3::ZERO, Vec3::Y);
+
+    // through the focal point, tan(fov / 2) to the side per unit forward
+    let (mut perspective, _) = app(Projection::Perspective(default()));
+    let tan = std::f32::consts::FRAC_PI_8.tan();
+    assert_ray(
+        ray_through(&mut perspective, facing_z, ndc),
+        Vec3::new(0.0, 0.0, 5.0),
+        Vec3::new(0.5 * tan, -0.25 * tan, -1.0),
+    );
+    assert_ray(
+        ray_through(&mut perspective, facing_x, ndc),
+        Vec3::new(3.0, 0.0, 0.0),
+        Vec3::new(-1.0, -0.25 * tan, -0.5 * tan),
+    );
+
+    // straight ahead, from the point on a 4x4 image plane
+    let (mut orthographic, _) = app(Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::Fixed {
+            width: 4.0,
+            height: 4.0,
+        },
+        ..default()
+    }));
+    assert_ray(
+        ray_through(&mut orthographic, facing_z, ndc),
+        Vec3::new(1.0, -0.5, 5.0),
+        Vec3::NEG_Z,
+    );
+    assert_ray(
+        ray_through(&mut orthographic, facing_x, ndc),
+        Vec3::new(3.0, -0.5, -1.0),
+        Vec3::NEG_X,
+    );
+}
+
+#[test]
+fn inset_viewport() {
+    let (mut app, _) = app(Projection::Perspective(default()));
+    // a 200x200 pane at (100, 100) in a 400x400 window
+    let mut camera = app.world.query::<&mut Camera>().single_mut(&mut app.world);
+    camera.viewport = Some(Viewport {
+        physical_position: UVec2::new(100, 100),
+        physical_size: UVec2::new(200, 200),
+        ..default()
+    });
+    let window = app
+        .world
+        .spawn(Window {
+            resolution: (400.0, 400.0).into(),
+            ..default()
+        })
+        .id();
+    let tan = std::f32::consts::FRAC_PI_8.tan();
+    for (position, ndc) in [
+        // the pane's top left corner is NDC (-1, 1), not the window's
+        (Vec2::new(100.0, 100.0), Vec2::new(-1.0, 1.0)),
+        (Vec2::new(200.0, 200.0), Vec2::ZERO),
+        (Vec2::new(300.0, 300.0), Vec2::new(1.0, -1.0)),
+    ] {
+        app.world.send_event(CursorMoved { window, position });
+        app.update();
+        let direction = app.world.resource::<PickRay>().direction.normalize();
+        let expected = (ndc * tan).extend(-1.0).normalize();
+        assert!(
+            direction.abs_diff_eq(expected, 1e-4),
+            "{position}: {direction}"
+        );
+    }
+}
+
+/// Every `HoverStart` and `HoverEnd` as ("start" or "end", entity, frame), in the order the
+/// `HoverEnd` docs say to read them
+#[derive(Resource, Default)]
+struct HoverSequence(Vec<(&'static str, Entity, u32)>);
+
+fn record_hover_sequence(
+    mut ends: EventReader<HoverEnd>,
+    mut starts: EventReader<HoverStart>,
+    mut sequence: ResMut<HoverSequence>,
+) {
+    let ends = ends.read().map(|end| ("end", end.hovered, end.frame));
+    let starts = starts
+        .read()
+        .map(|start| ("start", start.hovered, start.frame));
+    sequence.0.extend(ends.chain(starts));
+}
+
+#[test]
+fn switch_ends_before_starting() {
+    let (mut app, left) = app(Projection::Perspective(default()));
+    app.init_resource::<HoverSequence>()
+        .add_systems(Last, record_hover_sequence);
+    // two quads side by side with a gap between them, so the cursor can jump straight across
+    app.world.get_mut::<Transform>(left).unwrap().translation.x = -1.2;
+    let mesh = app.world.get::<Handle<Mesh>>(left).unwrap().clone();
+    let right = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(1.2, 0.0, 0.0)),
+            Hoverable,
+        ))
+        .id();
+    let ndc = 1.2 / (5.0 * std::f32::consts::FRAC_PI_8.tan());
+
+    run(&mut app, Vec2::new(-ndc, 0.0));
+    app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::new(ndc, 0.0));
+    let switch_frame = app.world.resource::<FrameCount>().0;
+    app.update();
+    let sequence = &app.world.resource::<HoverSequence>().0;
+    assert_eq!(sequence.len(), 3, "{sequence:?}");
+    assert_eq!((sequence[0].0, sequence[0].1), ("start", left));
+    assert_eq!(
+        sequence[1..],
+        [("end", left, switch_frame), ("start", right, switch_frame)]
+    );
+    // never both
+    assert!(app.world.get::<Hover>(left).is_none());
+    assert!(app.world.get::<Hover>(right).is_some());
+}
+
+#[test]
+fn sticky_hover() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.resource_mut::<PickingSettings>().sticky = true;
+    // overlapping the right half of the quad, nearer by less than `sticky_margin`
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let nearer = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(1.5, 0.0, 0.05)),
+            Hoverable,
+        ))
+        .id();
+    let at = |x: f32| Vec2::new(x / (5.0 * std::f32::consts::FRAC_PI_8.tan()), 0.0);
+    let hovered = |app: &App| app.world.resource::<Hovered>().inner;
+
+    // onto the quad alone, then over the overlap
+    run(&mut app, at(-0.5));
+    assert_eq!(hovered(&app), Some(quad));
+    run(&mut app, at(0.75));
+    assert_eq!(hovered(&app), Some(quad));
+    // off the quad, onto the other alone
+    run(&mut app, at(1.25));
+    assert_eq!(hovered(&app), Some(nearer));
+
+    // a clearly nearer hit does take over
+    run(&mut app, at(-0.5));
+    run(&mut app, at(0.75));
+    assert_eq!(hovered(&app), Some(quad));
+    app.world
+        .get_mut::<Transform>(nearer)
+        .unwrap()
+        .translation
+        .z = 0.5;
+    run(&mut app, at(0.75));
+    assert_eq!(hovered(&app), Some(nearer));
+
+    // and without `sticky`, any nearer hit
+    app.world
+        .get_mut::<Transform>(nearer)
+        .unwrap()
+        .translation
+        .z = 0.05;
+    app.world.resource_mut::<PickingSettings>().sticky = false;
+    run(&mut app, at(-0.5));
+    run(&mut app, at(0.75));
+    assert_eq!(hovered(&app), Some(nearer));
+}
+
+#[test]
+fn ignored_entities() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // a copy of the quad in front of it, e.g. one being dragged
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let front = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 1.0)),
+            Hoverable,
+        ))
+        .id();
+    app.update();
+    let ray = Ray {
+        origin: Vec3::new(0.0, 0.0, 5.0),
+        direction: Vec3::NEG_Z,
+    };
+    assert_eq!(cast_ray(&mut app.world, ray)[0].entity, front);
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(front));
+
+    app.world.resource_mut::<IgnoredEntities>().0.insert(front);
+    let hits = cast_ray(&mut app.world, ray);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entity, quad);
+    assert!((hits[0].distance - 5.0).abs() < 1e-4);
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(quad));
+}
+
+#[test]
+fn higher_layer_wins_over_nearer() {
+    let (mut app, far) = app(Projection::Perspective(default()));
+    let mesh = app.world.get::<Handle<Mesh>>(far).unwrap().clone();
+    let near = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 2.0)),
+            Hoverable,
+        ))
+        .id();
+    // both on layer 0, depth decides
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(near));
+
+    // the far one on a UI layer above the world
+    app.world.entity_mut(far).insert(HoverLayer(1));
+    run(&mut app, Vec2::ZERO);
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(far));
+    // the near one wherever the far one isn't hit
+    run(&mut app, Vec2::new(0.6, 0.0));
+    assert_eq!(app.world.resource::<Hovered>().inner, Some(near));
+}
+
+#[test]
+fn cached_bounds_match_brute_force() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    // 2 scattered triangles, none of their vertices shared
+    let positions: Vec<[f32; 3]> = vec![
+        [-0.7, 0.2, 0.3],
+        [1.3, -0.4, -0.2],
+        [0.1, 0.9, 0.6],
+        [0.4, -1.1, 0.1],
+        [-0.2, 0.3, -0.8],
+        [0.9, 0.6, 0.4],
+    ];
+    let (min, max) = positions
+        .iter()
+        .map(|p| Vec3::from(*p))
+        .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), p| {
+            (min.min(p), max.max(p))
+        });
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2, 3, 4, 5])));
+    let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+    let offset = Vec3::new(0.5, 0.0, -1.0);
+    let entity = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_translation(offset)),
+            Hoverable,
+        ))
+        .id();
+    app.update();
+    let aabb = *app.world.get::<Aabb>(entity).unwrap();
+    assert!(Vec3::from(aabb.min()).abs_diff_eq(min, 1e-6));
+    assert!(Vec3::from(aabb.max()).abs_diff_eq(max, 1e-6));
+
+    // rays just inside each face of the brute force box get past the cached bounds, rays just
+    // outside are rejected by them alone
+    let center = (min + max) / 2.0 + offset;
+    for axis in 0..3 {
+        let direction = Vec3::AXES[(axis + 1) % 3];
+        for (bound, outward) in [(min[axis], -1.0), (max[axis], 1.0)] {
+            for (inset, rejected) in [(-0.01, 0), (0.01, 1)] {
+                let mut point = center;
+                point[axis] = bound + offset[axis] + outward * inset;
+                app.world.resource_mut::<PickRayOverride>().0 = Some(PickRay {
+                    origin: point - direction * 10.0,
+                    direction,
+                });
+                app.update();
+                let stats = *app.world.resource::<PickingStats>();
+                assert_eq!(
+                    stats.broad_phase_rejected, rejected,
+                    "axis {axis} at {point}: {stats:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn focus_lost_mid_press() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut clicks = ManualEventReader::<HoverClick>::default();
+    let mut cancels = ManualEventReader::<HoverPressCancel>::default();
+    let mut drag_ends = ManualEventReader::<DragEnd>::default();
+    fn button(app: &mut App, pressed: bool) {
+        let mut input = app.world.resource_mut::<Input<MouseButton>>();
+        if pressed {
+            input.press(MouseButton::Left);
+        } else {
+            input.release(MouseButton::Left);
+        }
+        app.update();
+        app.world.resource_mut::<Input<MouseButton>>().clear();
+    }
+
+    // pressed on the quad and dragged off it
+    run(&mut app, Vec2::ZERO);
+    button(&mut app, true);
+    run(&mut app, Vec2::splat(0.3));
+    run(&mut app, Vec2::splat(0.9));
+    assert_eq!(app.world.resource::<PointerCapture>().0, Some(quad));
+
+    // alt-tabbed away with the button still down
+    app.world.send_event(WindowFocused {
+        window: Entity::PLACEHOLDER,
+        focused: false,
+    });
+    app.update();
+    let cancelled = drain(&app, &mut cancels);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].entity, quad);
+    let ended = drain(&app, &mut drag_ends);
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].entity, quad);
+    assert_eq!(app.world.resource::<PointerCapture>().0, None);
+
+    // the release arriving late is neither a click nor another cancel
+    button(&mut app, false);
+    app.update();
+    assert!(drain(&app, &mut clicks).is_empty());
+    assert!(drain(&app, &mut cancels).is_empty());
+    assert!(drain(&app, &mut drag_ends).is_empty());
+
+    // and the next press on the quad clicks as usual
+    run(&mut app, Vec2::ZERO);
+    button(&mut app, true);
+    button(&mut app, false);
+    app.update();
+    assert_eq!(drain(&app, &mut clicks).len(), 1);
+    assert!(drain(&app, &mut cancels).is_empty());
+}
+
+#[test]
+fn drag_delta_on_plane() {
+    /// Presses on the quad, drags the cursor right across NDC (0.5, 0) in steps, and releases
+    /// Returns the sum of the `Drag::delta`s
+    fn drag_across(app: &mut App) -> Vec3 {
+        let mut drags = ManualEventReader::<Drag>::default();
+        run(app, Vec2::ZERO);
+        drain(app, &mut drags);
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        app.world.resource_mut::<Input<MouseButton>>().clear();
+        // read after every step, events only last 2 frames
+        let mut moved = Vec3::ZERO;
+        for step in 1..=5 {
+            run(app, Vec2::new(step as f32 * 0.1, 0.0));
+            moved += drain(app, &mut drags)
+                .iter()
+                .map(|drag| drag.delta)
+                .sum::<Vec3>();
+        }
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .release(MouseButton::Left);
+        app.update();
+        app.world.resource_mut::<Input<MouseButton>>().clear();
+        moved
+    }
+    let (mut app, _) = app(Projection::Perspective(default()));
+    let half_extent = std::f32::consts::FRAC_PI_8.tan();
+
+    // the default plane through the quad, 5 units from the camera
+    let moved = drag_across(&mut app);
+    let expected = Vec3::new(0.5 * 5.0 * half_extent, 0.0, 0.0);
+    assert!(moved.abs_diff_eq(expected, 1e-4), "{moved}");
+
+    // twice as far from the camera, twice as far across it
+    app.world.resource_mut::<PickingSettings>().drag_plane = DragPlane {
+        point: Vec3::new(0.0, 0.0, -5.0),
+        normal: Vec3::Z,
+    };
+    let moved = drag_across(&mut app);
+    assert!(moved.abs_diff_eq(expected * 2.0, 1e-4), "{moved}");
+}
+
+/// Collects the messages of warnings logged on the thread it's the default subscriber of
+struct Warnings(Arc<std::sync::Mutex<Vec<String>>>);
+
+impl<S: bevy::utils::tracing::Subscriber> tracing_subscriber::Layer<S> for Warnings {
+    fn on_event(
+        &self,
+        event: &bevy::utils::tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        struct Message(String);
+        impl bevy::utils::tracing::field::Visit for Message {
+            fn record_debug(
+                &mut self,
+                field: &bevy::utils::tracing::field::Field,
+                value: &dyn std::fmt::Debug,
+            ) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+        if *event.metadata().level() != bevy::utils::tracing::Level::WARN {
+            return;
+        }
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.0.lock().unwrap().push(message.0);
+    }
+}
+
+#[test]
+fn unpickable_warning_once() {
+    use bevy::ecs::schedule::ExecutorKind;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    let warnings = Arc::default();
+    let subscriber = Registry::default().with(Warnings(Arc::clone(&warnings)));
+    // the app is made, run and dropped under the subscriber, with `--features trace` its systems
+    // hold spans that only this subscriber knows
+    bevy::utils::tracing::subscriber::with_default(subscriber, || {
+        let (mut app, _) = app(Projection::Perspective(default()));
+        // on this thread, where the subscriber is
+        for (_, schedule) in app.world.resource_mut::<Schedules>().iter_mut() {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        }
+        let mut points = Mesh::new(PrimitiveTopology::PointList);
+        points.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 1.0], [0.5, 0.5, 1.0]],
+        );
+        let points = app.world.resource_mut::<Assets<Mesh>>().add(points);
+        for _ in 0..2 {
+            app.world
+                .spawn((points.clone(), SpatialBundle::default(), Hoverable));
+        }
+        run(&mut app, Vec2::ZERO);
+        run(&mut app, Vec2::splat(0.5));
+    });
+    let warnings = warnings.lock().unwrap();
+    let unpickable: Vec<&String> = warnings
+        .iter()
+        .filter(|message| message.contains("can't be picked"))
+        .collect();
+    assert_eq!(unpickable.len(), 1, "{warnings:?}");
+    assert!(unpickable[0].contains("PointList"), "{}", unpickable[0]);
+}
+
+#[test]
+fn missing_aabbs_inserted() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    // an `Aabb` the hoverable already has is left alone
+    let own = Aabb::from_min_max(Vec3::splat(-3.0), Vec3::splat(3.0));
+    app.world.entity_mut(quad).insert(own);
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Mesh::from(shape::Box::new(2.0, 4.0, 6.0)));
+    let boxed = app
+        .world
+        .spawn((
+            mesh.clone(),
+            SpatialBundle::from_transform(Transform::from_xyz(5.0, 0.0, 0.0)),
+            Hoverable,
+        ))
+        .id();
+    app.update();
+    // in model space, the entity's transform doesn't change it
+    let aabb = *app.world.get::<Aabb>(boxed).unwrap();
+    assert_eq!(Vec3::from(aabb.center), Vec3::ZERO);
+    assert_eq!(Vec3::from(aabb.half_extents), Vec3::new(1.0, 2.0, 3.0));
+
+    // recomputed when the mesh changes
+    *app.world
+        .resource_mut::<Assets<Mesh>>()
+        .get_mut(&mesh)
+        .unwrap() = Mesh::from(shape::Box::from_corners(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 2.0, 4.0),
+    ));
+    app.update();
+    app.update();
+    let aabb = *app.world.get::<Aabb>(boxed).unwrap();
+    assert_eq!(Vec3::from(aabb.center), Vec3::new(0.5, 1.0, 2.0));
+    assert_eq!(Vec3::from(aabb.half_extents), Vec3::new(0.5, 1.0, 2.0));
+    assert_eq!(
+        Vec3::from(app.world.get::<Aabb>(quad).unwrap().half_extents),
+        Vec3::splat(3.0)
+    );
+}
+
+#[test]
+fn tab_focus_order() {
+    let (mut app, _) = app(Projection::Perspective(default()));
+    // a 2x2 grid of focusables, spawned out of reading order
+    let [bottom_right, top_left, bottom_left, top_right] =
+        [(1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, 1.0)].map(|(x, y)| {
+            app.world
+                .spawn((
+                    SpatialBundle::from_transform(Transform::from_xyz(x, y, 0.0)),
+                    Focusable,
+                ))
+                .id()
+        });
+    app.update();
+    fn press(app: &mut App, keys: &[KeyCode]) -> Option<Entity> {
+        let mut input = app.world.resource_mut::<Input<KeyCode>>();
+        for key in keys {
+            input.press(*key);
+        }
+        app.update();
+        app.world.resource_mut::<Input<KeyCode>>().reset_all();
+        app.world.resource::<Focused>().0
+    }
+    assert_eq!(app.world.resource::<Focused>().0, None);
+
+    // top to bottom, then left to right, and around again
+    for expected in [top_left, top_right, bottom_left, bottom_right, top_left] {
+        assert_eq!(press(&mut app, &[KeyCode::Tab]), Some(expected));
+    }
+    // and back
+    for expected in [bottom_right, bottom_left] {
+        assert_eq!(
+            press(&mut app, &[KeyCode::ShiftLeft, KeyCode::Tab]),
+            Some(expected)
+        );
+    }
+}
+
+/// What `Update` systems saw as hovered, every frame
+#[derive(Resource, Default)]
+struct HoveredInUpdate(Vec<Option<Entity>>);
+
+fn record_hovered_in_update(hovered: Res<Hovered>, mut seen: ResMut<HoveredInUpdate>) {
+    seen.0.push(hovered.inner);
+}
+
+#[test]
+fn pre_update_schedule() {
+    for (schedule, seen) in [(PickSchedule::PreUpdate, 0), (PickSchedule::PostUpdate, 1)] {
+        let (mut app, quad) = app_in(schedule, Projection::Perspective(default()));
+        app.init_resource::<HoveredInUpdate>()
+            .add_systems(Update, record_hovered_in_update);
+        // settle transforms and the camera first, the picking systems read last frame's
+        // in `PreUpdate`
+        app.update();
+        app.update();
+        app.world.resource_mut::<HoveredInUpdate>().0.clear();
+
+        // picked before `Update` in the frame the cursor arrives, or only after it
+        app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::ZERO);
+        app.update();
+        app.update();
+        let mut expected = vec![Some(quad); 2];
+        expected[..seen].fill(None);
+        assert_eq!(
+            app.world.resource::<HoveredInUpdate>().0,
+            expected,
+            "{schedule:?}"
+        );
+    }
+}
+
+#[test]
+fn initial_hover_changed_baseline() {
+    for initial in [false, true] {
+        let (mut app, quad) = app(Projection::Perspective(default()));
+        app.world
+            .resource_mut::<PickingSettings>()
+            .initial_hover_changed = initial;
+        let mut changes = ManualEventReader::<HoverChanged>::default();
+        let transitions = |app: &App, changes: &mut ManualEventReader<HoverChanged>| {
+            drain(app, changes)
+                .iter()
+                .map(|changed| (changed.from, changed.to))
+                .collect::<Vec<_>>()
+        };
+
+        // a few frames with nothing hovered, the baseline is only sent on the first
+        // read every frame, events only last 2
+        app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::splat(0.9));
+        let mut sent = Vec::new();
+        for _ in 0..4 {
+            app.update();
+            sent.extend(transitions(&app, &mut changes));
+        }
+        let expected = if initial { vec![(None, None)] } else { vec![] };
+        assert_eq!(sent, expected, "{initial}");
+
+        app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::ZERO);
+        let mut sent = Vec::new();
+        for _ in 0..4 {
+            app.update();
+            sent.extend(transitions(&app, &mut changes));
+        }
+        assert_eq!(sent, [(None, Some(quad))], "{initial}");
+    }
+}
+
+#[test]
+fn ray_hit_on_known_triangle() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    app.world.despawn(quad);
+    // the hit triangle is the second one, wound counter-clockwise seen from +Z
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [5.0, 5.0, 0.0],
+            [6.0, 5.0, 0.0],
+            [5.0, 6.0, 0.0],
+        ],
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_UV_0,
+        vec![
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [0.0, 1.0],
+            [0.0, 0.0],
+            [0.0, 0.0],
+            [0.0, 0.0],
+        ],
+    );
+    mesh.set_indices(Some(Indices::U32(vec![3, 4, 5, 0, 1, 2])));
+    let mesh = app.world.resource_mut::<Assets<Mesh>>().add(mesh);
+    // turned a quarter around Y, so the triangle faces +X: local (x, y, z) is at world (z, y, -x)
+    let entity = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_rotation(Quat::from_rotation_y(
+                std::f32::consts::FRAC_PI_2,
+            ))),
+            Hoverable,
+        ))
+        .id();
+    app.update();
+
+    // at local (0.5, 0.5, 0), a quarter of the way along both edges from vertex 0
+    let ray = Ray {
+        origin: Vec3::new(5.0, 0.5, -0.5),
+        direction: Vec3::NEG_X,
+    };
+    let hits = cast_ray(&mut app.world, ray);
+    assert_eq!(hits.len(), 1);
+    let hit = hits[0];
+    assert_eq!(hit.entity, entity);
+    assert!((hit.distance - 5.0).abs() < 1e-4, "{hit:?}");
+    assert!(
+        hit.point.abs_diff_eq(Vec3::new(0.0, 0.5, -0.5), 1e-4),
+        "{hit:?}"
+    );
+    assert!(hit.normal.unwrap().abs_diff_eq(Vec3::X, 1e-4), "{hit:?}");
+    assert_eq!(hit.triangle, Some([0, 1, 2]));
+    assert!(hit
+        .barycentric
+        .unwrap()
+        .abs_diff_eq(Vec3::new(0.5, 0.25, 0.25), 1e-4));
+    assert!(
+        hit.uv.unwrap().abs_diff_eq(Vec2::new(0.25, 0.25), 1e-4),
+        "{hit:?}"
+    );
+    let vertices = hit.vertices.unwrap();
+    for (vertex, expected) in vertices.into_iter().zip([
+        Vec3::ZERO,
+        Vec3::new(0.0, 0.0, -2.0),
+        Vec3::new(0.0, 2.0, 0.0),
+    ]) {
+        assert!(vertex.abs_diff_eq(expected, 1e-4), "{vertices:?}");
+    }
+}
+
+#[test]
+fn hover_move_buttons() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    let mut moves = ManualEventReader::<HoverMove>::default();
+    let mut move_to = |app: &mut App, x: f32| {
+        app.world.resource_mut::<VirtualCursor>().0 = Some(Vec2::new(x, 0.0));
+        app.update();
+        drain(app, &mut moves)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    run(&mut app, Vec2::ZERO);
+    assert!(move_to(&mut app, 0.0).is_empty());
+
+    // painting across the quad with the left button held
+    app.world
+        .resource_mut::<Input<MouseButton>>()
+        .press(MouseButton::Left);
+    let moved = move_to(&mut app, 0.1);
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0].hovered, quad);
+    assert_eq!(moved[0].buttons, vec![MouseButton::Left]);
+    assert!(moved[0].hit.point.x > 0.0);
+    // the hit didn't move, nothing to report
+    assert!(move_to(&mut app, 0.1).is_empty());
+
+    app.world
+        .resource_mut::<Input<MouseButton>>()
+        .release(MouseButton::Left);
+    let moved = move_to(&mut app, 0.2);
+    assert_eq!(moved.len(), 1);
+    assert!(moved[0].buttons.is_empty());
+    assert!(moved[0].hit.point.x > 0.1);
+}
+
+#[test]
+fn one_start_and_end_per_entity_per_frame() {
+    let (mut app, quad) = app(Projection::Perspective(default()));
+    {
+        let mut settings = app.world.resource_mut::<PickingSettings>();
+        settings.mode = HoverMode::Polling;
+        settings.hover_band = Some(1.0);
+    }
+    // a second quad just behind the first, both are hovered together
+    let mesh = app.world.get::<Handle<Mesh>>(quad).unwrap().clone();
+    let behind = app
+        .world
+        .spawn((
+            mesh,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, -0.1)),
+            Hoverable,
+        ))
+        .id();
+    let mut starts = ManualEventReader::<HoverStart>::default();
+    let mut ends = ManualEventReader::<HoverEnd>::default();
+    let mut started: Vec<(Entity, u32)> = Vec::new();
+    let mut ended: Vec<(Entity, u32)> = Vec::new();
+    for x in [0.0, 0.0, 3.0, 0.0, 3.0, 3.0, 0.0] {
+        app.world.resource_mut::<PickRayOverride>().0 = Some(PickRay {
+            origin: Vec3::new(x, 0.0, 5.0),
+            direction: Vec3::NEG_Z,
+        });
+        app.update();
+        started.extend(
+            drain(&app, &mut starts)
+                .iter()
+                .map(|s| (s.hovered, s.frame)),
+        );
+        ended.extend(drain(&app, &mut ends).iter().map(|e| (e.hovered, e.frame)));
+    }
+
+    for events in [&started, &ended] {
+        let mut unique = events.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), events.len(), "{events:?}");
+    }
+    for entity in [quad, behind] {
+        assert_eq!(started.iter().filter(|(e, _)| *e == entity).count(), 4);
+        assert_eq!(ended.iter().filter(|(e, _)| *e == entity).count(), 2);
+    }
+}
+
+#[cfg(any(feature = "gpu_picking", feature = "depth_picking"))]
+mod gpu_backends {
+    use super::*;
+    use bevy::core_pipeline::prepass::DepthPrepass;
+    use bevy::render::render_resource::TextureUsages;
+    use bevy::window::ExitCondition;
+    use bevy::winit::WinitPlugin;
+    use bevy_hover::PickingBackend;
+
+    /// App with the renderer, a camera 5 units away along +Z rendering to an image, and no window
+    fn render_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(
+            DefaultPlugins
+                .build()
+                .disable::<WinitPlugin>()
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                }),
+        )
+        .add_plugins(MouseRayPlugin);
+
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 100,
+                height: 100,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+        let target = app.world.resource_mut::<Assets<Image>>().add(image);
+        app.world.spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(target),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+                ..default()
+            },
+            RaySource::Mouse,
+        ));
+        app
+    }
+
+    /// Quads stacked at the center, the nearest on top, and one off to the side
+    fn spawn_quad_stack(app: &mut App) {
+        let mesh = app
+            .world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(shape::Quad::new(Vec2::splat(2.0))));
+        let material = app
+            .world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial::default());
+        for translation in [
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.5, 0.5, 2.0),
+            Vec3::new(3.0, 0.0, 0.0),
+        ] {
+            app.world.spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    transform: Transform::from_translation(translation),
+                    ..default()
+                },
+                Hoverable,
+            ));
+        }
+    }
+
+    /// Hovers the same entity with `backend` as on the CPU, at cursors on each quad of the stack and
+    /// off all of them
+    fn assert_backend_matches_cpu(app: &mut App, backend: PickingBackend) {
+        for cursor in [
+            Vec2::ZERO,
+            Vec2::new(0.3, 0.3),
+            Vec2::new(-0.5, -0.5),
+            Vec2::new(0.9, -0.9),
+        ] {
+            app.world.resource_mut::<PickingSettings>().backend = PickingBackend::Cpu;
+            run(app, cursor);
+            let cpu = app.world.resource::<Hovered>().inner;
+
+            app.world.resource_mut::<PickingSettings>().backend = backend;
+            // until something is read back picking stays on the CPU, which rejects the hoverables
+            // the ray misses; a readback leaves only the entity under the cursor to test
+            let read_back = (0..200).any(|_| {
+                app.update();
+                app.world.resource::<PickingStats>().broad_phase_rejected == 0
+            });
+            assert!(read_back, "nothing read back at {cursor}");
+            assert_eq!(app.world.resource::<Hovered>().inner, cpu, "at {cursor}");
+        }
+    }
+
+    #[cfg(feature = "gpu_picking")]
+    #[test]
+    #[ignore = "needs a GPU, run with `--features gpu_picking -- --ignored`"]
+    fn gpu_backend_matches_cpu() {
+        let mut app = render_app();
+        spawn_quad_stack(&mut app);
+        assert_backend_matches_cpu(&mut app, PickingBackend::Gpu);
+    }
+
+    #[cfg(feature = "depth_picking")]
+    #[test]
+    #[ignore = "needs a GPU, run with `--features depth_picking -- --ignored`"]
+    fn depth_backend_matches_cpu() {
+        let mut app = render_app();
+        spawn_quad_stack(&mut app);
+        let camera = app
+            .world
+            .query_filtered::<Entity, With<Camera>>()
+            .single(&app.world);
+        app.world.entity_mut(camera).insert(DepthPrepass);
+        // the prepass depth is multisampled by default
+        assert_backend_matches_cpu(&mut app, PickingBackend::Depth);
+        app.insert_resource(Msaa::Off);
+        assert_backend_matches_cpu(&mut app, PickingBackend::Depth);
+    }
+}